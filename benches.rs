@@ -83,6 +83,32 @@ fn cloning(c: &mut Criterion) {
     });
 }
 
+fn column_scans(c: &mut Criterion) {
+    let mut c = c.benchmark_group("Column Scans");
+
+    c.bench_function("Table::col sum", |b| {
+        let table = Table::from(vec_of_vecs());
+        b.iter(|| {
+            (0..table.cols_len())
+                .flat_map(|i| table.col(i).unwrap())
+                .filter_map(Entry::num)
+                .map(|n| n.as_f64())
+                .sum::<f64>()
+        })
+    });
+
+    c.bench_function("ColumnTable::nums sum", |b| {
+        let table = ColumnTable::from(Table::from(vec_of_vecs()));
+        b.iter(|| {
+            (0..table.cols_len())
+                .filter_map(|i| table.nums(i))
+                .flat_map(|(nums, _)| nums)
+                .map(|n| n.as_f64())
+                .sum::<f64>()
+        })
+    });
+}
+
 fn parse_csv(c: &mut Criterion) {
     let mut c = c.benchmark_group("Parse CSV");
 
@@ -97,5 +123,5 @@ fn parse_csv(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, adding_rows, cloning, parse_csv);
+criterion_group!(benches, adding_rows, cloning, column_scans, parse_csv);
 criterion_main!(benches);
@@ -0,0 +1,244 @@
+use super::*;
+use std::ops::{Index, IndexMut};
+
+/// A reference to a column, either by index or by header name.
+///
+/// Names are matched against the [`Entry::as_str`] representation of the header row, and only
+/// resolve if the table has a header row.
+pub trait ColumnRef<T> {
+    /// Resolve to a column index of `table`.
+    fn resolve(&self, table: &Table<T>) -> Result<usize, TableError>;
+}
+
+impl<T> ColumnRef<T> for usize {
+    fn resolve(&self, table: &Table<T>) -> Result<usize, TableError> {
+        TableError::check_col(*self, table.cols_len()).map(|_| *self)
+    }
+}
+
+impl<T: AsRef<str>> ColumnRef<T> for &str {
+    fn resolve(&self, table: &Table<T>) -> Result<usize, TableError> {
+        table
+            .col_by_name(self)
+            .ok_or_else(|| TableError::UnknownColumn(self.to_string()))
+    }
+}
+
+impl<T> Table<T> {
+    /// The index of the first column with the header `name`.
+    ///
+    /// Returns `None` if no header matches, or if the table does not have a header row.
+    pub fn col_by_name(&self, name: &str) -> Option<usize>
+    where
+        T: AsRef<str>,
+    {
+        self.data
+            .first()
+            .filter(|_| self.header)?
+            .iter()
+            .position(|e| e.as_str() == name)
+    }
+
+    /// Retrieve the entry at `row` and `col`, where `col` is an index or header name.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let mut r: Table<&str> = Table::from(vec![
+    ///     vec![Obj("cut"), Obj("price")],
+    ///     vec![Obj("Ideal"), Num(326.into())],
+    /// ]);
+    ///
+    /// assert_eq!(r.get(1, 1), Some(&Num(326.into())));
+    /// assert_eq!(r.get(1, "price"), Some(&Num(326.into())));
+    /// assert_eq!(r.get(1, "carat"), None);
+    /// assert_eq!(r.get(2, 0), None);
+    ///
+    /// r.set(1, "price", Num(400.into()));
+    /// assert_eq!(r[(1, 1)], Num(400.into()));
+    /// ```
+    pub fn get<C: ColumnRef<T>>(&self, row: usize, col: C) -> Option<&Entry<T>> {
+        let col = col.resolve(self).ok()?;
+        self.data.get(row).map(|x| &x[col])
+    }
+
+    /// Retrieve the mutable entry at `row` and `col`, where `col` is an index or header name.
+    pub fn get_mut<C: ColumnRef<T>>(&mut self, row: usize, col: C) -> Option<&mut Entry<T>> {
+        let col = col.resolve(self).ok()?;
+        self.data.get_mut(row).map(|x| &mut x[col])
+    }
+
+    /// Set the entry at `row` and `col`, returning the previous entry.
+    ///
+    /// # Panics
+    /// Panics if `row` or `col` is outside the table bounds, or `col` names an unknown column. See
+    /// [`Table::try_set`].
+    pub fn set<C: ColumnRef<T>>(&mut self, row: usize, col: C, entry: Entry<T>) -> Entry<T> {
+        or_panic(self.try_set(row, col, entry))
+    }
+
+    /// Set the entry at `row` and `col`, returning the previous entry.
+    ///
+    /// Returns an error if `row` or `col` is outside the table bounds, or `col` names an unknown
+    /// column.
+    pub fn try_set<C: ColumnRef<T>>(
+        &mut self,
+        row: usize,
+        col: C,
+        entry: Entry<T>,
+    ) -> Result<Entry<T>, TableError> {
+        let col = col.resolve(self)?;
+        TableError::check_row(row, self.rows_len())?;
+        Ok(std::mem::replace(&mut self.data[row][col], entry))
+    }
+
+    /// Swap rows `a` and `b`.
+    ///
+    /// Note that the header row can be swapped.
+    ///
+    /// # Panics
+    /// Panics if `a` or `b` is outside the rows bounds. See [`Table::try_swap_rows`].
+    pub fn swap_rows(&mut self, a: usize, b: usize) -> &mut Self {
+        or_panic(self.try_swap_rows(a, b))
+    }
+
+    /// Swap rows `a` and `b`.
+    ///
+    /// Returns an error if `a` or `b` is outside the rows bounds.
+    pub fn try_swap_rows(&mut self, a: usize, b: usize) -> Result<&mut Self, TableError> {
+        TableError::check_row(a, self.rows_len())?;
+        TableError::check_row(b, self.rows_len())?;
+        self.data.swap(a, b);
+        Ok(self)
+    }
+
+    /// Swap columns `a` and `b`.
+    ///
+    /// # Panics
+    /// Panics if `a` or `b` is outside the columns bounds. See [`Table::try_swap_cols`].
+    pub fn swap_cols(&mut self, a: usize, b: usize) -> &mut Self {
+        or_panic(self.try_swap_cols(a, b))
+    }
+
+    /// Swap columns `a` and `b`.
+    ///
+    /// Returns an error if `a` or `b` is outside the columns bounds.
+    pub fn try_swap_cols(&mut self, a: usize, b: usize) -> Result<&mut Self, TableError> {
+        TableError::check_col(a, self.cols_len())?;
+        TableError::check_col(b, self.cols_len())?;
+        for row in &mut self.data {
+            row.swap(a, b);
+        }
+        Ok(self)
+    }
+
+    fn cell(&self, row: usize, col: usize) -> Result<(usize, usize), TableError> {
+        TableError::check_row(row, self.rows_len())?;
+        TableError::check_col(col, self.cols_len())?;
+        Ok((row, col))
+    }
+}
+
+/// Index an entry by `(row, col)`.
+///
+/// # Panics
+/// Panics if `row` or `col` is outside the table bounds.
+impl<T> Index<(usize, usize)> for Table<T> {
+    type Output = Entry<T>;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Entry<T> {
+        let (row, col) = or_panic(self.cell(row, col));
+        &self.data[row][col]
+    }
+}
+
+/// Mutably index an entry by `(row, col)`.
+///
+/// # Panics
+/// Panics if `row` or `col` is outside the table bounds.
+impl<T> IndexMut<(usize, usize)> for Table<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Entry<T> {
+        let (row, col) = or_panic(self.cell(row, col));
+        &mut self.data[row][col]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n(x: i32) -> Entry<&'static str> {
+        Num(x.into())
+    }
+
+    fn table() -> Table<&'static str> {
+        Table::from(vec![
+            vec![Obj("a"), Obj("b"), Obj("c")],
+            vec![n(1), n(2), n(3)],
+            vec![n(4), n(5), Nil],
+        ])
+    }
+
+    #[test]
+    fn getting() {
+        let mut t = table();
+        assert_eq!(t.col_by_name("c"), Some(2));
+        assert_eq!(t.get(2, 1), Some(&n(5)));
+        assert_eq!(t.get(2, "b"), Some(&n(5)));
+        assert_eq!(t.get(0, "a"), Some(&Obj("a")));
+        assert_eq!(t.get(3, "a"), None);
+        assert_eq!(t.get(1, 3), None);
+        assert_eq!(t.get(1, "d"), None);
+
+        *t.get_mut(2, "c").unwrap() = n(6);
+        t[(1, 0)] = n(0);
+        assert_eq!(t[(2, 2)], n(6));
+        assert_eq!(t[(1, 0)], n(0));
+
+        t.set_header(false);
+        assert_eq!(t.col_by_name("a"), None);
+        assert_eq!(t.get(1, "a"), None);
+    }
+
+    #[test]
+    fn setting_and_swapping() {
+        let mut t = table();
+        assert_eq!(t.set(1, "b", n(7)), n(2));
+        assert_eq!(t.try_set(1, 0, Nil), Ok(n(1)));
+        assert_eq!(
+            t.try_set(1, "d", Nil),
+            Err(TableError::UnknownColumn("d".to_string()))
+        );
+        assert_eq!(
+            t.try_set(3, 0, Nil),
+            Err(TableError::RowOutOfBounds { index: 3, rows: 3 })
+        );
+
+        t.swap_rows(1, 2).swap_cols(0, 2);
+        assert_eq!(
+            t.into_raw(),
+            vec![
+                vec![Obj("c"), Obj("b"), Obj("a")],
+                vec![Nil, n(5), n(4)],
+                vec![n(3), n(7), Nil],
+            ]
+        );
+
+        let mut t = table();
+        assert!(t.try_swap_cols(0, 3).is_err());
+        assert!(t.try_swap_rows(3, 0).is_err());
+        assert_eq!(t, table());
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_panic() {
+        let _ = &table()[(0, 3)];
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_panic() {
+        table().set(0, "d", Nil);
+    }
+}
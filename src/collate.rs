@@ -0,0 +1,210 @@
+//! String comparisons which follow human expectations rather than byte order.
+//!
+//! Each comparison falls back to byte order when strings are otherwise equal, such that the
+//! ordering is _total_ and consistent with string equality.
+use std::{cmp::Ordering, iter::Peekable, str::Chars};
+
+/// Natural, numeric aware ordering: `file2 < file10`.
+///
+/// Runs of ascii digits are compared by numeric value, other characters are compared by code
+/// point.
+pub fn natural(a: &str, b: &str) -> Ordering {
+    let mut x = a.chars().peekable();
+    let mut y = b.chars().peekable();
+
+    loop {
+        let ord = match (x.peek().copied(), y.peek().copied()) {
+            (None, None) => break a.cmp(b),
+            (None, Some(_)) => Ordering::Less,
+            (Some(_), None) => Ordering::Greater,
+            (Some(c), Some(d)) if c.is_ascii_digit() && d.is_ascii_digit() => {
+                digits(&mut x).cmp_by_value(digits(&mut y))
+            }
+            (Some(c), Some(d)) => {
+                x.next();
+                y.next();
+                c.cmp(&d)
+            }
+        };
+
+        if ord.is_ne() {
+            break ord;
+        }
+    }
+}
+
+/// Case insensitive ordering: `apple < Zebra`.
+///
+/// Characters are compared by their lowercase mapping.
+pub fn case_insensitive(a: &str, b: &str) -> Ordering {
+    let x = a.chars().flat_map(char::to_lowercase);
+    let y = b.chars().flat_map(char::to_lowercase);
+    x.cmp(y).then_with(|| a.cmp(b))
+}
+
+/// A locale independent, multi-level collation approximating the Unicode root collation.
+///
+/// Strings are compared in levels, with each level only breaking ties of the previous:
+/// 1. _Primary_: base characters, ignoring case and diacritics. Whitespace and punctuation sort
+///    before symbols, which sort before digits, which sort before letters.
+/// 2. _Secondary_: diacritics, unaccented characters first.
+/// 3. _Tertiary_: case, lowercase first.
+///
+/// Diacritic folding covers the Latin-1 Supplement and Latin Extended-A blocks, along with
+/// combining diacritical marks. Characters outside these ranges are compared by code point at the
+/// primary level.
+pub fn collation(a: &str, b: &str) -> Ordering {
+    let primary = |s: &'_ str| {
+        s.chars()
+            .filter(|&c| !is_combining(c))
+            .map(|c| {
+                let base = fold(c);
+                (class(base), base)
+            })
+            .collect::<Vec<_>>()
+    };
+    let secondary = |s: &'_ str| {
+        s.chars()
+            .map(|c| match c {
+                c if is_combining(c) => Some(c),
+                c if fold(c) != c.to_lowercase().next().unwrap_or(c) => Some(c),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    let tertiary = |s: &'_ str| s.chars().map(char::is_uppercase).collect::<Vec<_>>();
+
+    primary(a)
+        .cmp(&primary(b))
+        .then_with(|| secondary(a).cmp(&secondary(b)))
+        .then_with(|| tertiary(a).cmp(&tertiary(b)))
+        .then_with(|| a.cmp(b))
+}
+
+/// A run of ascii digits, with leading zeros stripped.
+struct Digits(String);
+
+impl Digits {
+    fn cmp_by_value(self, rhs: Digits) -> Ordering {
+        self.0
+            .len()
+            .cmp(&rhs.0.len())
+            .then_with(|| self.0.cmp(&rhs.0))
+    }
+}
+
+fn digits(chars: &mut Peekable<Chars>) -> Digits {
+    let mut s = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        if !(s.is_empty() && c == '0') {
+            s.push(c);
+        }
+    }
+    Digits(s)
+}
+
+fn class(c: char) -> u8 {
+    if c.is_whitespace() || c.is_ascii_punctuation() {
+        0
+    } else if c.is_numeric() {
+        2
+    } else if c.is_alphabetic() {
+        3
+    } else {
+        1
+    }
+}
+
+fn is_combining(c: char) -> bool {
+    matches!(c, '\u{0300}'..='\u{036F}')
+}
+
+/// Folds a character into its lowercase base character, stripping diacritics.
+fn fold(c: char) -> char {
+    match c {
+        'À'..='Å' | 'à'..='å' | 'Ā'..='ą' => 'a',
+        'Ç' | 'ç' | 'Ć'..='č' => 'c',
+        'Ď'..='đ' => 'd',
+        'È'..='Ë' | 'è'..='ë' | 'Ē'..='ě' => 'e',
+        'Ĝ'..='ģ' => 'g',
+        'Ĥ'..='ħ' => 'h',
+        'Ì'..='Ï' | 'ì'..='ï' | 'Ĩ'..='ı' => 'i',
+        'Ĵ' | 'ĵ' => 'j',
+        'Ķ'..='ĸ' => 'k',
+        'Ĺ'..='ł' => 'l',
+        'Ñ' | 'ñ' | 'Ń'..='ň' => 'n',
+        'Ò'..='Ö' | 'Ø' | 'ò'..='ö' | 'ø' | 'Ō'..='ő' => 'o',
+        'Ŕ'..='ř' => 'r',
+        'ß' | 'Ś'..='š' => 's',
+        'Ţ'..='ŧ' => 't',
+        'Ù'..='Ü' | 'ù'..='ü' | 'Ũ'..='ų' => 'u',
+        'Ŵ' | 'ŵ' => 'w',
+        'Ý' | 'ý' | 'ÿ' | 'Ŷ'..='Ÿ' => 'y',
+        'Ź'..='ž' => 'z',
+        c => c.to_lowercase().next().unwrap_or(c),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Ordering::*;
+
+    fn sorted(mut v: Vec<&str>, f: fn(&str, &str) -> Ordering) -> Vec<&str> {
+        v.sort_by(|a, b| f(a, b));
+        v
+    }
+
+    #[test]
+    fn natural_ordering() {
+        assert_eq!(natural("file2", "file10"), Less);
+        assert_eq!(natural("file10", "file10"), Equal);
+        assert_eq!(natural("file010", "file10"), Less);
+        assert_eq!(natural("file1a", "file1b"), Less);
+        assert_eq!(natural("a", "a1"), Less);
+        assert_eq!(
+            sorted(vec!["x10y2", "x2y10", "x10y10", "x2y2"], natural),
+            vec!["x2y2", "x2y10", "x10y2", "x10y10"]
+        );
+        assert_eq!(
+            natural(
+                "99999999999999999999999999999999999999999",
+                "100000000000000000000000000000000000000000"
+            ),
+            Less
+        );
+    }
+
+    #[test]
+    fn case_insensitive_ordering() {
+        assert_eq!(case_insensitive("apple", "Zebra"), Less);
+        assert_eq!(case_insensitive("Apple", "apple"), Less);
+        assert_eq!(case_insensitive("apple", "apple"), Equal);
+        assert_eq!(
+            sorted(vec!["b", "C", "a", "B"], case_insensitive),
+            vec!["a", "B", "b", "C"]
+        );
+    }
+
+    #[test]
+    fn collation_ordering() {
+        assert_eq!(collation("apple", "Zebra"), Less);
+        assert_eq!(collation("éclair", "eclair"), Greater);
+        assert_eq!(collation("éclair", "ezra"), Less);
+        assert_eq!(collation("e\u{0301}clair", "ezra"), Less);
+        assert_eq!(collation("resume", "Resume"), Less);
+        assert_eq!(collation("Łódź", "Lodz"), Greater);
+        assert_eq!(collation("Łódź", "Lods"), Greater);
+        assert_eq!(collation("Łódź", "Lodza"), Less);
+        assert_eq!(collation("-x", "1"), Less);
+        assert_eq!(collation("1", "a"), Less);
+        assert_eq!(collation("straße", "strasse"), Less);
+        assert_eq!(
+            sorted(
+                vec!["Zoë", "zoe", "Zoe", "zebra", "Ärger", "arg"],
+                collation
+            ),
+            vec!["arg", "Ärger", "zebra", "zoe", "Zoe", "Zoë"]
+        );
+    }
+}
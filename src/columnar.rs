@@ -0,0 +1,1095 @@
+use super::*;
+use std::mem::{replace, take};
+
+/// A data table stored column-major.
+///
+/// Each column is kept contiguous. Columns consisting of only [`Num`] and [`Nil`] _data_ entries
+/// are stored as an unboxed buffer of [`Number`]s with a [`NullBitmap`]; a column is boxed into
+/// [`Entry`]s once an [`Obj`] entry is added. The header row is stored separately such that it
+/// does not affect the column storage.
+///
+/// `ColumnTable` mirrors the structural part of the [`Table`] API: adding, inserting and removing
+/// rows and columns, sorting, retaining and reversing rows, and mapping entries. It differs where
+/// the storage does:
+/// - entries are returned as `Entry<&T>` since numeric entries are not stored as `Entry`s; for
+///   the same reason there is no `row_mut` or `col_mut`, use [`ColumnTable::set`] instead.
+/// - the header row is read with [`ColumnTable::header`] rather than a field, since changing it
+///   moves entries into or out of the columns.
+///
+/// Conversion to and from a `Table` moves the entries, which gives access to the rest of the
+/// `Table` API.
+///
+/// ```rust
+/// # use table::*;
+/// use Entry::*;
+/// let mut r: ColumnTable<&str> = ColumnTable::new();
+/// r.add_rows(
+///     vec![
+///         vec![Obj("name"), Obj("price")].into_iter(),
+///         vec![Obj("a"), Num(1.into())].into_iter(),
+///         vec![Obj("b"), Nil].into_iter(),
+///     ].into_iter()
+/// );
+///
+/// assert_eq!((r.rows_len(), r.cols_len()), (3, 2));
+/// assert_eq!(r.col(1).unwrap().collect::<Vec<_>>(), vec![Obj(&"price"), Num(1.into()), Nil]);
+///
+/// let (nums, nulls) = r.nums(1).unwrap();
+/// assert_eq!(nums[0], 1.into());
+/// assert_eq!(nulls.null_count(), 1);
+/// assert!(r.nums(0).is_none());
+///
+/// let t = Table::from(r);
+/// assert_eq!(t.row(2).unwrap().nth(1), Some(&Nil));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ColumnTable<T> {
+    head: Option<Vec<Entry<T>>>,
+    cols: Vec<Column<T>>,
+    header: bool,
+    /// Number of _data_ rows.
+    rows: usize,
+}
+
+/// A validity bitmap, tracking which entries of a numeric column are [`Nil`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct NullBitmap {
+    words: Vec<u64>,
+    len: usize,
+}
+
+#[derive(Debug, Clone)]
+enum Column<T> {
+    /// Numbers, with the bitmap marking valid entries. Nil entries hold zero.
+    Num(Vec<Number>, NullBitmap),
+    Entries(Vec<Entry<T>>),
+}
+
+impl<T> ColumnTable<T> {
+    /// Construct a new column table.
+    pub fn new() -> Self {
+        Self {
+            head: None,
+            cols: Vec::new(),
+            header: true,
+            rows: 0,
+        }
+    }
+
+    /// Treat the first row as a header row. Defaults to `true`.
+    pub fn header(&self) -> bool {
+        self.header
+    }
+
+    /// Set whether to treat the first row as a header row.
+    ///
+    /// Moving a row into or out of the header shifts each column.
+    pub fn set_header(&mut self, has: bool) -> &mut Self {
+        match (has, self.head.take()) {
+            (true, None) if self.rows > 0 => {
+                let mut head = Vec::with_capacity(self.cols.len());
+                for col in &mut self.cols {
+                    let mut entries = take(col).into_entries().into_iter();
+                    head.push(entries.next().unwrap_or_default());
+                    *col = Column::from_entries(entries);
+                }
+                self.rows -= 1;
+                self.head = Some(head);
+            }
+            (false, Some(head)) => {
+                for (col, h) in self.cols.iter_mut().zip(head) {
+                    *col = Column::from_entries(once(h).chain(take(col).into_entries()));
+                }
+                self.rows += 1;
+            }
+            (_, head) => self.head = head,
+        }
+        self.header = has;
+        self
+    }
+
+    /// Add a row of entries into the table.
+    ///
+    /// Rows are padded with [`Nil`] in the same way as [`Table::add_row`].
+    pub fn add_row<I, E>(&mut self, entries: I) -> &mut Self
+    where
+        I: Iterator<Item = E>,
+        E: Into<Entry<T>>,
+    {
+        self.add_rows(once(entries))
+    }
+
+    /// Add rows of entries into the table.
+    ///
+    /// Rows are padded with [`Nil`] in the same way as [`Table::add_rows`].
+    pub fn add_rows<I, J, E>(&mut self, rows: I) -> &mut Self
+    where
+        I: Iterator<Item = J>,
+        J: Iterator<Item = E>,
+        E: Into<Entry<T>>,
+    {
+        for row in rows {
+            if self.header && self.head.is_none() {
+                let head = row.map(Into::into).collect::<Vec<_>>();
+                self.widen(head.len());
+                self.head = Some(head);
+                self.pad_head();
+                continue;
+            }
+
+            let mut c = 0;
+            for e in row {
+                self.widen(c + 1);
+                self.cols[c].push(e.into());
+                c += 1;
+            }
+            self.cols[c..].iter_mut().for_each(|x| x.push(Nil));
+            self.rows += 1;
+        }
+        self
+    }
+
+    /// Add a column of entries into the table.
+    ///
+    /// Columns are padded with [`Nil`] in the same way as [`Table::add_col`].
+    pub fn add_col<I, E>(&mut self, entries: I) -> &mut Self
+    where
+        I: Iterator<Item = E>,
+        E: Into<Entry<T>>,
+    {
+        self.add_cols(once(entries))
+    }
+
+    /// Add columns of entries into the table.
+    ///
+    /// Columns are padded with [`Nil`] in the same way as [`Table::add_cols`].
+    pub fn add_cols<I, J, E>(&mut self, cols: I) -> &mut Self
+    where
+        I: Iterator<Item = J>,
+        J: Iterator<Item = E>,
+        E: Into<Entry<T>>,
+    {
+        for col in cols {
+            let mut col = col.map(Into::into);
+            if self.header && (self.head.is_some() || self.rows == 0) {
+                let h = col.next();
+                if h.is_some() || self.head.is_some() {
+                    let head = self.head.get_or_insert_with(Vec::new);
+                    head.resize_with(self.cols.len(), Default::default);
+                    head.push(h.unwrap_or_default());
+                }
+            }
+
+            let mut column = Column::from_entries(col);
+            let len = column.len();
+            if len > self.rows {
+                let extra = len - self.rows;
+                self.cols
+                    .iter_mut()
+                    .for_each(|x| x.extend(repeat_with(Entry::default).take(extra)));
+                self.rows = len;
+            }
+            column.extend(repeat_with(Entry::default).take(self.rows - len));
+            self.cols.push(column);
+            self.pad_head();
+        }
+        self
+    }
+
+    /// Remove a column.
+    ///
+    /// Unlike [`Table::remove_col`], this does not touch the other columns.
+    ///
+    /// # Panics
+    /// Panics if `index` is outside the columns bounds. See [`ColumnTable::try_remove_col`].
+    pub fn remove_col(&mut self, index: usize) -> &mut Self {
+        or_panic(self.try_remove_col(index))
+    }
+
+    /// Remove a column.
+    ///
+    /// Returns an error if `index` is outside the columns bounds.
+    pub fn try_remove_col(&mut self, index: usize) -> Result<&mut Self, TableError> {
+        TableError::check_col(index, self.cols_len())?;
+        self.cols.remove(index);
+        if let Some(head) = &mut self.head {
+            head.remove(index);
+        }
+        if self.cols.is_empty() {
+            self.head = None;
+            self.rows = 0;
+        }
+        Ok(self)
+    }
+
+    /// Insert a row of entries at the specified row index, shifting trailing rows down.
+    ///
+    /// Rows are padded with [`Nil`] in the same way as [`Table::insert_row`]. Inserting at index
+    /// zero of a table with a header row makes the new row the header.
+    ///
+    /// # Panics
+    /// Panics if `index` is outside the rows bounds. See [`ColumnTable::try_insert_row`].
+    pub fn insert_row<I, E>(&mut self, index: usize, entries: I) -> &mut Self
+    where
+        I: Iterator<Item = E>,
+        E: Into<Entry<T>>,
+    {
+        or_panic(self.try_insert_row(index, entries))
+    }
+
+    /// Insert a row of entries at the specified row index, shifting trailing rows down.
+    ///
+    /// Returns an error if `index` is outside the rows bounds.
+    pub fn try_insert_row<I, E>(
+        &mut self,
+        index: usize,
+        entries: I,
+    ) -> Result<&mut Self, TableError>
+    where
+        I: Iterator<Item = E>,
+        E: Into<Entry<T>>,
+    {
+        if index > self.rows_len() {
+            return Err(TableError::RowOutOfBounds {
+                index,
+                rows: self.rows_len(),
+            });
+        }
+
+        let mut row = entries.map(Into::into).collect::<Vec<_>>();
+        self.widen(row.len());
+        row.resize_with(self.cols_len(), Default::default);
+        match (&mut self.head, index) {
+            (Some(head), 0) => {
+                let old = replace(head, row);
+                self.insert_data_row(0, old);
+            }
+            (Some(_), i) => self.insert_data_row(i - 1, row),
+            (None, _) if self.header => self.head = Some(row),
+            (None, i) => self.insert_data_row(i, row),
+        }
+        Ok(self)
+    }
+
+    /// Insert a column of entries at the specified column index, shifting trailing columns right.
+    ///
+    /// Columns are padded with [`Nil`] in the same way as [`Table::insert_col`].
+    ///
+    /// # Panics
+    /// Panics if `index` is outside the columns bounds. See [`ColumnTable::try_insert_col`].
+    pub fn insert_col<I, E>(&mut self, index: usize, entries: I) -> &mut Self
+    where
+        I: Iterator<Item = E>,
+        E: Into<Entry<T>>,
+    {
+        or_panic(self.try_insert_col(index, entries))
+    }
+
+    /// Insert a column of entries at the specified column index, shifting trailing columns right.
+    ///
+    /// Unlike [`Table::try_insert_col`], this does not touch the other columns unless `entries`
+    /// is longer than them.
+    ///
+    /// Returns an error if `index` is outside the columns bounds.
+    pub fn try_insert_col<I, E>(
+        &mut self,
+        index: usize,
+        entries: I,
+    ) -> Result<&mut Self, TableError>
+    where
+        I: Iterator<Item = E>,
+        E: Into<Entry<T>>,
+    {
+        if index > self.cols_len() {
+            return Err(TableError::ColOutOfBounds {
+                index,
+                cols: self.cols_len(),
+            });
+        }
+
+        self.add_col(entries);
+        self.cols[index..].rotate_right(1);
+        if let Some(head) = &mut self.head {
+            head[index..].rotate_right(1);
+        }
+        Ok(self)
+    }
+
+    /// Remove a row of entries at the specified row index, shifting trailing rows up.
+    ///
+    /// Removing the header row makes the first data row the header, as with a [`Table`].
+    ///
+    /// # Panics
+    /// Panics if `index` is outside the rows bounds. See [`ColumnTable::try_remove_row`].
+    pub fn remove_row(&mut self, index: usize) -> &mut Self {
+        or_panic(self.try_remove_row(index))
+    }
+
+    /// Remove a row of entries at the specified row index, shifting trailing rows up.
+    ///
+    /// Returns an error if `index` is outside the rows bounds.
+    pub fn try_remove_row(&mut self, index: usize) -> Result<&mut Self, TableError> {
+        TableError::check_row(index, self.rows_len())?;
+        match (&self.head, index) {
+            (Some(_), 0) => self.replace_head(),
+            (Some(_), i) => self.remove_data_row(i - 1),
+            (None, i) => self.remove_data_row(i),
+        }
+        self.clear_if_empty();
+        Ok(self)
+    }
+
+    /// Set the entry at `row` and `col`.
+    ///
+    /// Numeric entries are not stored as `Entry`s, so there is no `row_mut` or `col_mut`, entries
+    /// are replaced one at a time instead. Setting an [`Obj`] entry boxes a numeric column.
+    ///
+    /// # Panics
+    /// Panics if `row` or `col` is outside the table bounds. See [`ColumnTable::try_set`].
+    pub fn set<E: Into<Entry<T>>>(&mut self, row: usize, col: usize, entry: E) -> &mut Self {
+        or_panic(self.try_set(row, col, entry))
+    }
+
+    /// Set the entry at `row` and `col`. See [`ColumnTable::set`].
+    ///
+    /// Returns an error if `row` or `col` is outside the table bounds.
+    pub fn try_set<E: Into<Entry<T>>>(
+        &mut self,
+        row: usize,
+        col: usize,
+        entry: E,
+    ) -> Result<&mut Self, TableError> {
+        TableError::check_row(row, self.rows_len())?;
+        TableError::check_col(col, self.cols_len())?;
+        let entry = entry.into();
+        match (&mut self.head, row) {
+            (Some(head), 0) => head[col] = entry,
+            (Some(_), i) => self.cols[col].set(i - 1, entry),
+            (None, i) => self.cols[col].set(i, entry),
+        }
+        Ok(self)
+    }
+
+    /// Table has no data in it.
+    pub fn is_empty(&self) -> bool {
+        self.cols.is_empty()
+    }
+
+    /// Table has no data below the header row.
+    pub fn is_data_empty(&self) -> bool {
+        self.is_empty() || self.rows == 0
+    }
+
+    /// The number of rows of data, including the header.
+    pub fn rows_len(&self) -> usize {
+        self.rows + self.head.is_some() as usize
+    }
+
+    /// The number of columns of data.
+    pub fn cols_len(&self) -> usize {
+        self.cols.len()
+    }
+
+    /// Retrieve a row of entries.
+    pub fn row(&self, index: usize) -> Option<impl Iterator<Item = Entry<&T>>> {
+        if index < self.rows_len() {
+            Some((0..self.cols_len()).map(move |c| self.entry(index, c)))
+        } else {
+            None
+        }
+    }
+
+    /// Retrieve a column of entries.
+    pub fn col(&self, index: usize) -> Option<impl Iterator<Item = Entry<&T>>> {
+        let col = self.cols.get(index)?;
+        let head = self.head.as_ref().map(|h| h[index].as_ref());
+        Some(
+            head.into_iter()
+                .chain((0..self.rows).map(move |i| col.get(i))),
+        )
+    }
+
+    /// Iterate over the rows.
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = Entry<&T>>> {
+        (0..self.rows_len()).filter_map(move |i| self.row(i))
+    }
+
+    /// Iterate over the columns.
+    pub fn cols(&self) -> impl Iterator<Item = impl Iterator<Item = Entry<&T>>> {
+        (0..self.cols_len()).filter_map(move |i| self.col(i))
+    }
+
+    /// Sort _data_ rows by comparing entries in a column. See [`Table::sort`].
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds. See [`ColumnTable::try_sort`].
+    ///
+    /// # Parallelisation
+    /// The row order is found by sorting the one column, the columns are then reordered in
+    /// parallel.
+    pub fn sort<F>(&mut self, col: usize, ordering: F)
+    where
+        T: Send,
+        F: Fn(&Entry<T>, &Entry<T>) -> std::cmp::Ordering,
+    {
+        or_panic(self.try_sort(col, ordering))
+    }
+
+    /// Sort _data_ rows by comparing entries in a column. See [`ColumnTable::sort`].
+    ///
+    /// Returns an error if `col` is outside the columns bounds.
+    pub fn try_sort<F>(&mut self, col: usize, ordering: F) -> Result<(), TableError>
+    where
+        T: Send,
+        F: Fn(&Entry<T>, &Entry<T>) -> std::cmp::Ordering,
+    {
+        TableError::check_col(col, self.cols_len())?;
+
+        let mut order = (0..self.rows).collect::<Vec<_>>();
+        match &self.cols[col] {
+            Column::Num(v, b) => {
+                let get = |i: usize| -> Entry<T> {
+                    if b.is_valid(i) {
+                        Num(v[i])
+                    } else {
+                        Nil
+                    }
+                };
+                order.sort_by(|&x, &y| ordering(&get(x), &get(y)));
+            }
+            Column::Entries(v) => order.sort_by(|&x, &y| ordering(&v[x], &v[y])),
+        }
+        self.cols.par_iter_mut().for_each(|c| c.select(&order));
+        Ok(())
+    }
+
+    /// Retain rows that match the predicate `p`.
+    ///
+    /// The predicate supplies the row index and the row entries. As with [`Table::retain_rows`]
+    /// the header row is included. If it is not retained, the first retained row becomes the
+    /// header.
+    pub fn retain_rows<P>(&mut self, p: P)
+    where
+        P: Fn(usize, std::slice::Iter<Entry<&T>>) -> bool,
+    {
+        let keep = self
+            .rows()
+            .enumerate()
+            .map(|(i, row)| p(i, row.collect::<Vec<_>>().iter()))
+            .collect::<Vec<_>>();
+        let s = self.head.is_some() as usize;
+        let rows = (0..self.rows).filter(|i| keep[i + s]).collect::<Vec<_>>();
+        self.cols.iter_mut().for_each(|c| c.select(&rows));
+        self.rows = rows.len();
+        if s == 1 && !keep[0] {
+            self.replace_head();
+        }
+        self.clear_if_empty();
+    }
+
+    /// Reverse the order of the _data_ rows, in place.
+    pub fn reverse_rows(&mut self) {
+        let rows = (0..self.rows).rev().collect::<Vec<_>>();
+        self.cols.iter_mut().for_each(|c| c.select(&rows));
+    }
+
+    /// Reverse the order of the columns, in place.
+    ///
+    /// Unlike [`Table::reverse_cols_par`], this does not touch the entries.
+    pub fn reverse_cols(&mut self) {
+        self.cols.reverse();
+        if let Some(head) = &mut self.head {
+            head.reverse();
+        }
+    }
+
+    /// Map each entry `Entry<T> -> Entry<U>` using a morphism. See [`Table::map`].
+    ///
+    /// Each column is stored anew, so a mapped column with only [`Num`] and [`Nil`] data entries
+    /// is unboxed.
+    ///
+    /// # Parallelisation
+    /// Each column is mapped in parallel.
+    pub fn map<U, F>(self, f: F) -> ColumnTable<U>
+    where
+        T: Send,
+        U: Send,
+        F: Fn(Entry<T>) -> Entry<U> + Sync,
+    {
+        ColumnTable {
+            head: self.head.map(|h| h.into_iter().map(&f).collect()),
+            cols: self
+                .cols
+                .into_par_iter()
+                .map(|c| Column::from_entries(c.into_entries().into_iter().map(&f)))
+                .collect(),
+            header: self.header,
+            rows: self.rows,
+        }
+    }
+
+    /// Map each entry `Entry<T> -> Entry<U>` using a morphism `T -> U`. See [`Table::map_obj`].
+    ///
+    /// Numeric columns are moved without being mapped.
+    ///
+    /// # Parallelisation
+    /// Each column is mapped in parallel.
+    pub fn map_obj<U, F>(self, f: F) -> ColumnTable<U>
+    where
+        T: Send,
+        U: Send,
+        F: Fn(T) -> U + Sync,
+    {
+        let map = |e: Entry<T>| e.map_obj(&f);
+        ColumnTable {
+            head: self.head.map(|h| h.into_iter().map(map).collect()),
+            cols: self
+                .cols
+                .into_par_iter()
+                .map(|c| match c {
+                    Column::Num(v, b) => Column::Num(v, b),
+                    Column::Entries(v) => Column::Entries(v.into_iter().map(map).collect()),
+                })
+                .collect(),
+            header: self.header,
+            rows: self.rows,
+        }
+    }
+
+    /// The unboxed numbers and null bitmap of the _data_ entries in column `index`.
+    ///
+    /// Returns `None` if the column is out of bounds or contains [`Obj`] entries. Numbers at
+    /// [`Nil`] positions are zero.
+    pub fn nums(&self, index: usize) -> Option<(&[Number], &NullBitmap)> {
+        match self.cols.get(index)? {
+            Column::Num(v, b) => Some((v, b)),
+            Column::Entries(_) => None,
+        }
+    }
+
+    /// Extracts the columns of the table, including the header entries.
+    pub fn into_raw_cols(self) -> Vec<Vec<Entry<T>>> {
+        let mut head = self.head.map(Vec::into_iter);
+        self.cols
+            .into_iter()
+            .map(|col| {
+                let h = head.as_mut().and_then(Iterator::next);
+                h.into_iter().chain(col.into_entries()).collect()
+            })
+            .collect()
+    }
+
+    fn entry(&self, row: usize, col: usize) -> Entry<&T> {
+        match (&self.head, row) {
+            (Some(head), 0) => head[col].as_ref(),
+            (Some(_), i) => self.cols[col].get(i - 1),
+            (None, i) => self.cols[col].get(i),
+        }
+    }
+
+    /// Add columns, filled with nil, up to `cols`.
+    fn widen(&mut self, cols: usize) {
+        while self.cols.len() < cols {
+            let mut col = Column::default();
+            col.extend(repeat_with(Entry::default).take(self.rows));
+            self.cols.push(col);
+        }
+        self.pad_head();
+    }
+
+    fn pad_head(&mut self) {
+        let cols = self.cols.len();
+        if let Some(head) = &mut self.head {
+            head.resize_with(cols, Default::default);
+        }
+    }
+
+    fn insert_data_row(&mut self, index: usize, row: Vec<Entry<T>>) {
+        self.cols
+            .iter_mut()
+            .zip(row)
+            .for_each(|(c, e)| c.insert(index, e));
+        self.rows += 1;
+    }
+
+    fn remove_data_row(&mut self, index: usize) {
+        self.cols.iter_mut().for_each(|c| c.remove(index));
+        self.rows -= 1;
+    }
+
+    /// Drop the header row, moving the first data row into the header.
+    fn replace_head(&mut self) {
+        self.head = None;
+        self.header = false;
+        self.set_header(true);
+    }
+
+    /// Mirror a `Table` with no rows, which has no columns.
+    fn clear_if_empty(&mut self) {
+        if self.rows_len() == 0 {
+            self.cols.clear();
+        }
+    }
+}
+
+impl<T> Default for ColumnTable<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// # Parallelisation
+/// The table is transposed in parallel, and each column is then stored in parallel.
+impl<T: Send> From<Table<T>> for ColumnTable<T> {
+    fn from(table: Table<T>) -> Self {
+        let header = table.header;
+        let has_head = header && !table.data.is_empty();
+        let s = has_head as usize;
+        let rows = table.rows_len() - s;
+
+        let (head, cols): (Vec<_>, Vec<_>) = table
+            .transpose_par()
+            .data
+            .into_par_iter()
+            .map(|col| {
+                let mut col = col.into_iter();
+                let h = if has_head { col.next() } else { None };
+                (h.unwrap_or_default(), Column::from_entries(col))
+            })
+            .unzip();
+
+        Self {
+            head: Some(head).filter(|_| has_head),
+            cols,
+            header,
+            rows,
+        }
+    }
+}
+
+/// # Parallelisation
+/// Each column is unboxed in parallel, and the table is then transposed in parallel.
+impl<T: Send> From<ColumnTable<T>> for Table<T> {
+    fn from(table: ColumnTable<T>) -> Self {
+        let header = table.header;
+        let mut head = table.head.map(Vec::into_iter);
+        let cols: Vec<Vec<_>> = table
+            .cols
+            .into_iter()
+            .map(|col| (head.as_mut().and_then(Iterator::next), col))
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .map(|(h, col)| h.into_iter().chain(col.into_entries()).collect::<Vec<_>>())
+            .collect();
+
+        let mut table = Table::from(cols).transpose_par();
+        table.header = header;
+        table
+    }
+}
+
+impl NullBitmap {
+    /// The number of entries tracked.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// No entries are tracked.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The entry at `index` is _not_ nil. Returns `false` if `index` is out of bounds.
+    pub fn is_valid(&self, index: usize) -> bool {
+        index < self.len && self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    /// The number of nil entries.
+    pub fn null_count(&self) -> usize {
+        self.len
+            - self
+                .words
+                .iter()
+                .map(|w| w.count_ones() as usize)
+                .sum::<usize>()
+    }
+
+    fn push(&mut self, valid: bool) {
+        if self.len == self.words.len() * 64 {
+            self.words.push(0);
+        }
+        if valid {
+            self.words[self.len / 64] |= 1 << (self.len % 64);
+        }
+        self.len += 1;
+    }
+
+    fn set(&mut self, index: usize, valid: bool) {
+        let bit = 1 << (index % 64);
+        if valid {
+            self.words[index / 64] |= bit;
+        } else {
+            self.words[index / 64] &= !bit;
+        }
+    }
+
+    fn insert(&mut self, index: usize, valid: bool) {
+        self.push(false);
+        for i in (index + 1..self.len).rev() {
+            self.set(i, self.is_valid(i - 1));
+        }
+        self.set(index, valid);
+    }
+
+    fn remove(&mut self, index: usize) {
+        for i in index + 1..self.len {
+            self.set(i - 1, self.is_valid(i));
+        }
+        // bits past the length must stay clear for `null_count`
+        self.set(self.len - 1, false);
+        self.len -= 1;
+        self.words.truncate(self.len.div_ceil(64));
+    }
+}
+
+impl<T> Column<T> {
+    fn from_entries<I: IntoIterator<Item = Entry<T>>>(entries: I) -> Self {
+        let mut col = Self::default();
+        col.extend(entries);
+        col
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Column::Num(v, _) => v.len(),
+            Column::Entries(v) => v.len(),
+        }
+    }
+
+    fn get(&self, i: usize) -> Entry<&T> {
+        match self {
+            Column::Num(v, b) if b.is_valid(i) => Num(v[i]),
+            Column::Num(..) => Nil,
+            Column::Entries(v) => v[i].as_ref(),
+        }
+    }
+
+    fn push(&mut self, entry: Entry<T>) {
+        match (self, entry) {
+            (Column::Num(v, b), Num(n)) => {
+                v.push(n);
+                b.push(true);
+            }
+            (Column::Num(v, b), Nil) => {
+                v.push(Number::from(0));
+                b.push(false);
+            }
+            (col @ Column::Num(..), entry) => {
+                let mut entries = take(col).into_entries();
+                entries.push(entry);
+                *col = Column::Entries(entries);
+            }
+            (Column::Entries(v), entry) => v.push(entry),
+        }
+    }
+
+    fn extend<I: IntoIterator<Item = Entry<T>>>(&mut self, entries: I) {
+        entries.into_iter().for_each(|e| self.push(e));
+    }
+
+    fn insert(&mut self, index: usize, entry: Entry<T>) {
+        match (self, entry) {
+            (Column::Num(v, b), Num(n)) => {
+                v.insert(index, n);
+                b.insert(index, true);
+            }
+            (Column::Num(v, b), Nil) => {
+                v.insert(index, Number::from(0));
+                b.insert(index, false);
+            }
+            (col @ Column::Num(..), entry) => {
+                let mut entries = take(col).into_entries();
+                entries.insert(index, entry);
+                *col = Column::Entries(entries);
+            }
+            (Column::Entries(v), entry) => v.insert(index, entry),
+        }
+    }
+
+    fn set(&mut self, index: usize, entry: Entry<T>) {
+        match (self, entry) {
+            (Column::Num(v, b), Num(n)) => {
+                v[index] = n;
+                b.set(index, true);
+            }
+            (Column::Num(v, b), Nil) => {
+                v[index] = Number::from(0);
+                b.set(index, false);
+            }
+            (col @ Column::Num(..), entry) => {
+                let mut entries = take(col).into_entries();
+                entries[index] = entry;
+                *col = Column::Entries(entries);
+            }
+            (Column::Entries(v), entry) => v[index] = entry,
+        }
+    }
+
+    fn remove(&mut self, index: usize) {
+        match self {
+            Column::Num(v, b) => {
+                v.remove(index);
+                b.remove(index);
+            }
+            Column::Entries(v) => {
+                v.remove(index);
+            }
+        }
+    }
+
+    /// Keep the entries at `rows`, in that order.
+    fn select(&mut self, rows: &[usize]) {
+        match self {
+            Column::Num(v, b) => {
+                let mut nulls = NullBitmap::default();
+                rows.iter().for_each(|&i| nulls.push(b.is_valid(i)));
+                *v = rows.iter().map(|&i| v[i]).collect();
+                *b = nulls;
+            }
+            Column::Entries(v) => *v = rows.iter().map(|&i| take(&mut v[i])).collect(),
+        }
+    }
+
+    fn into_entries(self) -> Vec<Entry<T>> {
+        match self {
+            Column::Num(v, b) => v
+                .into_iter()
+                .enumerate()
+                .map(|(i, n)| if b.is_valid(i) { Num(n) } else { Nil })
+                .collect(),
+            Column::Entries(v) => v,
+        }
+    }
+}
+
+impl<T> Default for Column<T> {
+    fn default() -> Self {
+        Column::Num(Vec::new(), NullBitmap::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n(x: i32) -> Entry<&'static str> {
+        Num(x.into())
+    }
+
+    fn raw() -> Vec<Vec<Entry<&'static str>>> {
+        vec![
+            vec![Obj("a"), Obj("b"), Obj("c")],
+            vec![n(1), Obj("x"), Nil],
+            vec![n(2), Nil, Nil],
+            vec![Nil, Obj("y"), Nil],
+        ]
+    }
+
+    fn rows(t: &ColumnTable<&'static str>) -> Vec<Vec<Entry<&'static str>>> {
+        t.rows()
+            .map(|r| {
+                r.map(|e| e.obj().map_or(e.num().map_or(Nil, Num), |s| Obj(**s)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn storage() {
+        let t = ColumnTable::from(Table::from(raw()));
+        assert!(t.header());
+        assert_eq!((t.rows_len(), t.cols_len()), (4, 3));
+        assert_eq!(rows(&t), raw());
+
+        let (nums, nulls) = t.nums(0).unwrap();
+        assert_eq!(nums, &[1.into(), 2.into(), 0.into()][..]);
+        assert_eq!(nulls.len(), 3);
+        assert!(nulls.is_valid(1) && !nulls.is_valid(2) && !nulls.is_valid(3));
+        assert_eq!(nulls.null_count(), 1);
+        assert!(t.nums(1).is_none());
+        assert_eq!(t.nums(2).unwrap().1.null_count(), 3);
+
+        assert_eq!(Table::from(t), Table::from(raw()));
+
+        let mut b = NullBitmap::default();
+        (0..130).for_each(|i| b.push(i % 3 == 0));
+        assert_eq!(b.null_count(), 86);
+        assert!(b.is_valid(129) && !b.is_valid(128) && !b.is_valid(130));
+
+        let mut c = b.clone();
+        c.insert(0, false);
+        c.insert(64, true);
+        assert_eq!((c.len(), c.null_count()), (132, 87));
+        assert!(c.is_valid(1) && !c.is_valid(0) && c.is_valid(64) && c.is_valid(131));
+        c.remove(64);
+        c.remove(0);
+        assert_eq!(c, b);
+        (0..130).for_each(|_| c.remove(0));
+        assert_eq!(c, NullBitmap::default());
+    }
+
+    #[test]
+    fn adding() {
+        let mut t = ColumnTable::new();
+        t.add_rows(raw().into_iter().map(Vec::into_iter));
+        assert_eq!(rows(&t), raw());
+        assert!(t.nums(0).is_some());
+
+        t.add_row(vec![Obj("z")].into_iter());
+        assert!(t.nums(0).is_none());
+        assert_eq!(t.rows_len(), 5);
+
+        t.add_row(vec![n(0), n(0), n(0), n(9)].into_iter());
+        assert_eq!(t.cols_len(), 4);
+        assert_eq!(
+            t.col(3).unwrap().collect::<Vec<_>>(),
+            vec![Nil, Nil, Nil, Nil, Nil, Num(9.into())]
+        );
+
+        let mut t = ColumnTable::new();
+        t.add_col(vec![Obj("a"), n(1)].into_iter());
+        t.add_col(vec![Obj("b"), n(2), n(3)].into_iter());
+        t.add_col(once(Obj("c")));
+        assert_eq!(
+            rows(&t),
+            vec![
+                vec![Obj("a"), Obj("b"), Obj("c")],
+                vec![n(1), n(2), Nil],
+                vec![Nil, n(3), Nil],
+            ]
+        );
+        assert_eq!(
+            Table::from(t).into_raw(),
+            vec![
+                vec![Obj("a"), Obj("b"), Obj("c")],
+                vec![n(1), n(2), Nil],
+                vec![Nil, n(3), Nil],
+            ]
+        );
+    }
+
+    #[test]
+    fn header_and_removal() {
+        let mut t = ColumnTable::from(Table::from(raw()));
+        t.set_header(false);
+        assert_eq!(t.rows_len(), 4);
+        assert_eq!(rows(&t), raw());
+        assert!(t.nums(0).is_none());
+
+        t.set_header(true);
+        assert!(t.nums(0).is_some());
+        assert_eq!(rows(&t), raw());
+
+        t.remove_col(1);
+        assert_eq!(t.cols().map(|c| c.count()).collect::<Vec<_>>(), vec![4, 4]);
+        assert_eq!(t.row(0).unwrap().nth(1), Some(Obj(&"c")));
+        t.remove_col(0).remove_col(0);
+        assert!(t.is_empty());
+        assert_eq!(t.rows_len(), 0);
+
+        let t = ColumnTable::from(Table::<()>::new());
+        assert!(t.is_empty() && t.is_data_empty());
+    }
+
+    #[test]
+    fn mirrors_table() {
+        let mut t = Table::from(raw());
+        let mut c = ColumnTable::from(t.clone());
+        let check = |t: &Table<&'static str>, c: &ColumnTable<&'static str>| {
+            assert_eq!(&Table::from(c.clone()), t);
+        };
+
+        t.insert_row(2, vec![n(7), Obj("w")].into_iter());
+        c.insert_row(2, vec![n(7), Obj("w")].into_iter());
+        check(&t, &c);
+        assert!(c.nums(0).is_some() && c.nums(1).is_none());
+
+        t.insert_row(0, vec![Obj("h")].into_iter());
+        c.insert_row(0, vec![Obj("h")].into_iter());
+        check(&t, &c);
+        assert!(c.nums(0).is_none());
+
+        t.insert_col(1, (0..8).map(n));
+        c.insert_col(1, (0..8).map(n));
+        check(&t, &c);
+        assert_eq!(c.nums(1).unwrap().0.len(), 7);
+
+        t.remove_row(0).remove_row(3);
+        c.remove_row(0).remove_row(3);
+        check(&t, &c);
+
+        t[(1, 0)] = Nil;
+        t[(2, 3)] = Obj("o");
+        c.set(1, 0, Nil).set(2, 3, Obj("o"));
+        check(&t, &c);
+
+        t.sort(1, Entry::total_cmp);
+        c.sort(1, Entry::total_cmp);
+        check(&t, &c);
+        t.sort(2, Entry::total_cmp);
+        c.sort(2, Entry::total_cmp);
+        check(&t, &c);
+
+        t.reverse_rows();
+        t.reverse_cols_par();
+        c.reverse_rows();
+        c.reverse_cols();
+        check(&t, &c);
+
+        let p =
+            |i: usize, mut r: std::slice::Iter<Entry<&&str>>| i % 2 == 1 && r.all(|e| !e.is_nil());
+        t.retain_rows(|i, r| p(i, r.map(Entry::as_ref).collect::<Vec<_>>().iter()));
+        c.retain_rows(p);
+        check(&t, &c);
+
+        let (t, c) = (t.map_obj(str::len), c.map_obj(str::len));
+        assert_eq!(Table::from(c.clone()), t);
+        let f = |e: Entry<usize>| e.num().map_or(Nil, |x| Obj(x.as_f64() as usize));
+        assert_eq!(Table::from(c.map(f)), t.map(f));
+
+        let mut c = ColumnTable::from(Table::from(raw()));
+        c.retain_rows(|_, _| false);
+        assert!(c.is_empty() && c.rows_len() == 0);
+    }
+
+    #[test]
+    fn out_of_bounds() {
+        let mut c = ColumnTable::from(Table::from(raw()));
+        assert_eq!(
+            c.try_insert_row(5, once(Nil)).err(),
+            Some(TableError::RowOutOfBounds { index: 5, rows: 4 })
+        );
+        assert_eq!(
+            c.try_insert_col(4, once(Nil)).err(),
+            Some(TableError::ColOutOfBounds { index: 4, cols: 3 })
+        );
+        assert!(c.try_remove_row(4).is_err());
+        assert!(c.try_set(0, 3, Nil).is_err());
+        assert!(c.try_sort(3, Entry::total_cmp).is_err());
+
+        let mut c = ColumnTable::new();
+        c.insert_row(0, vec![Obj("a")].into_iter());
+        c.insert_row(1, vec![n(1)].into_iter());
+        assert!(c.header() && c.nums(0).is_some());
+        c.remove_row(0);
+        assert_eq!(rows(&c), vec![vec![n(1)]]);
+        c.remove_row(0);
+        assert!(c.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn remove_col_panic() {
+        ColumnTable::from(Table::from(raw())).remove_col(3);
+    }
+}
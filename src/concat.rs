@@ -0,0 +1,388 @@
+use super::*;
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    mem::take,
+};
+
+/// How columns are matched between tables in [`Table::concat`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Align {
+    /// Match columns by header entry.
+    ///
+    /// Repeated header entries within a table are matched by occurrence. Tables without a header
+    /// row are matched by position.
+    ByName,
+    /// Match columns by index.
+    ByPosition,
+}
+
+/// Which columns are kept in [`Table::concat`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ColumnSet {
+    /// Keep columns found in _any_ table, filling missing entries with [`Nil`].
+    Union,
+    /// Keep only columns found in _every_ table.
+    Intersect,
+}
+
+impl<T> Table<T> {
+    /// Vertically concatenate `tables`, aligning columns by name or position.
+    ///
+    /// The header flag and header row of the first table are used; the header rows of the
+    /// remaining tables are dropped. With [`ColumnSet::Union`], columns are ordered by first
+    /// occurrence and missing entries are filled with [`Nil`]. With [`ColumnSet::Intersect`],
+    /// columns are in the order of the first table.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let jan: Table<&str> = Table::from(vec![
+    ///     vec![Obj("region"), Obj("demand")],
+    ///     vec![Obj("NSW"), Num(1.into())],
+    /// ]);
+    /// let feb: Table<&str> = Table::from(vec![
+    ///     vec![Obj("price"), Obj("region")],
+    ///     vec![Num(50.into()), Obj("VIC")],
+    /// ]);
+    ///
+    /// let t = Table::concat(vec![jan, feb], Align::ByName, ColumnSet::Union);
+    /// assert_eq!(t.into_raw(), vec![
+    ///     vec![Obj("region"), Obj("demand"), Obj("price")],
+    ///     vec![Obj("NSW"), Num(1.into()), Nil],
+    ///     vec![Obj("VIC"), Nil, Num(50.into())],
+    /// ]);
+    /// ```
+    ///
+    /// # Parallelisation
+    /// The rows of each table are aligned in parallel.
+    pub fn concat<I>(tables: I, align: Align, set: ColumnSet) -> Table<T>
+    where
+        I: IntoIterator<Item = Table<T>>,
+        T: Hash + Eq + Send,
+    {
+        let mut tables = tables.into_iter().collect::<Vec<_>>();
+        let maps = match align {
+            Align::ByName => name_maps(&tables, set),
+            Align::ByPosition => position_maps(&tables, set),
+        };
+
+        let mut table = Table::new();
+        table.header = tables.first().map(|t| t.header).unwrap_or(true);
+        table.cols = maps.first().map(Vec::len).unwrap_or_default();
+
+        if table.header {
+            // each header entry is taken from the first table to have the column
+            let row = (0..table.cols)
+                .map(|j| {
+                    let (t, c) = tables
+                        .iter()
+                        .zip(&maps)
+                        .enumerate()
+                        .filter(|(_, (t, _))| t.header && !t.data.is_empty())
+                        .find_map(|(t, (_, map))| map[j].map(|c| (t, c)))?;
+                    Some(take(&mut tables[t].data[0][c]))
+                })
+                .map(Option::unwrap_or_default)
+                .collect();
+            table.data.push(row);
+        }
+
+        for (t, map) in tables.into_iter().zip(maps) {
+            let s = if t.header { 1 } else { 0 };
+            table
+                .data
+                .par_extend(t.data.into_par_iter().skip(s).map(|mut row| {
+                    map.iter()
+                        .map(|c| c.map(|c| take(&mut row[c])).unwrap_or_default())
+                        .collect()
+                }));
+        }
+
+        table.remove_empty_row_entries();
+        table
+    }
+
+    /// Horizontally stack the columns of `other` onto the right of this table.
+    ///
+    /// The header row of `other` is appended to this table's header row. If only this table has a
+    /// header row, the new header entries are [`Nil`]; if only `other` does, its header row is
+    /// dropped.
+    ///
+    /// # Panics
+    /// Panics if the number of _data_ rows differ. See [`Table::try_hstack`].
+    pub fn hstack(&mut self, other: Table<T>) -> &mut Self {
+        or_panic(self.try_hstack(other))
+    }
+
+    /// Horizontally stack the columns of `other` onto the right of this table. See
+    /// [`Table::hstack`].
+    ///
+    /// Returns an error if the number of _data_ rows differ, leaving this table unchanged.
+    pub fn try_hstack(&mut self, other: Table<T>) -> Result<&mut Self, TableError> {
+        let s = if self.header && !self.data.is_empty() {
+            1
+        } else {
+            0
+        };
+        let os = if other.header && !other.data.is_empty() {
+            1
+        } else {
+            0
+        };
+        let (rows, orows) = (self.rows_len() - s, other.rows_len() - os);
+        if rows != orows {
+            return Err(TableError::RowsMismatch { rows, other: orows });
+        }
+
+        let cols = other.cols;
+        let mut other = other.data.into_iter();
+        let header = if os == 1 { other.next() } else { None };
+        if self.header && (s == 1 || header.is_some()) {
+            if s == 0 {
+                self.data
+                    .push(repeat_with(Entry::default).take(self.cols).collect());
+            }
+            let header = header.unwrap_or_else(|| repeat_with(Entry::default).take(cols).collect());
+            self.data[0].extend(header);
+        }
+
+        let s = if self.header { 1 } else { 0 };
+        for (row, x) in self.data.iter_mut().skip(s).zip(other) {
+            row.extend(x);
+        }
+        self.cols += cols;
+
+        Ok(self)
+    }
+}
+
+/// Output column to source column mappings for each table, aligning by index.
+fn position_maps<T>(tables: &[Table<T>], set: ColumnSet) -> Vec<Vec<Option<usize>>> {
+    let widths = tables.iter().map(|t| t.cols);
+    let cols = match set {
+        ColumnSet::Union => widths.max(),
+        ColumnSet::Intersect => widths.min(),
+    }
+    .unwrap_or_default();
+
+    tables
+        .iter()
+        .map(|t| (0..cols).map(|c| (c < t.cols).then_some(c)).collect())
+        .collect()
+}
+
+/// Output column to source column mappings for each table, aligning by header entry.
+fn name_maps<T: Hash + Eq>(tables: &[Table<T>], set: ColumnSet) -> Vec<Vec<Option<usize>>> {
+    // header entries keyed with their occurrence, or None if a table has no header
+    let keys = tables
+        .iter()
+        .map(|t| {
+            let header = t.data.first().filter(|_| t.header)?;
+            let mut seen: HashMap<&Entry<T>, usize> = HashMap::new();
+            let ordered = header
+                .iter()
+                .map(|e| {
+                    let n = seen.entry(e).or_default();
+                    *n += 1;
+                    (e, *n)
+                })
+                .collect::<Vec<_>>();
+            let keys = ordered
+                .iter()
+                .enumerate()
+                .map(|(c, &k)| (k, c))
+                .collect::<HashMap<_, _>>();
+            Some((ordered, keys))
+        })
+        .collect::<Vec<_>>();
+
+    let mut named = keys.iter().flatten();
+    let mut cols = named
+        .next()
+        .map(|(ordered, _)| ordered.clone())
+        .unwrap_or_default();
+    match set {
+        ColumnSet::Union => {
+            let mut have = cols.iter().copied().collect::<HashSet<_>>();
+            for (ordered, _) in named {
+                for &k in ordered {
+                    if have.insert(k) {
+                        cols.push(k);
+                    }
+                }
+            }
+        }
+        ColumnSet::Intersect => {
+            for (_, keys) in named {
+                cols.retain(|k| keys.contains_key(k));
+            }
+        }
+    }
+
+    tables
+        .iter()
+        .zip(&keys)
+        .map(|(t, keys)| match keys {
+            Some((_, keys)) => cols.iter().map(|k| keys.get(k).copied()).collect(),
+            None => (0..cols.len()).map(|c| (c < t.cols).then_some(c)).collect(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n(x: i32) -> Entry<&'static str> {
+        Num(x.into())
+    }
+
+    fn tables() -> Vec<Table<&'static str>> {
+        vec![
+            Table::from(vec![
+                vec![Obj("a"), Obj("b")],
+                vec![n(1), n(2)],
+                vec![n(3), n(4)],
+            ]),
+            Table::from(vec![vec![Obj("c"), Obj("a")], vec![n(5), n(6)]]),
+            Table::from(vec![
+                vec![Obj("a"), Obj("b"), Obj("c")],
+                vec![n(7), n(8), n(9)],
+            ]),
+        ]
+    }
+
+    #[test]
+    fn concat_by_name() {
+        let t = Table::concat(tables(), Align::ByName, ColumnSet::Union);
+        assert!(t.header);
+        assert_eq!(
+            t.into_raw(),
+            vec![
+                vec![Obj("a"), Obj("b"), Obj("c")],
+                vec![n(1), n(2), Nil],
+                vec![n(3), n(4), Nil],
+                vec![n(6), Nil, n(5)],
+                vec![n(7), n(8), n(9)],
+            ]
+        );
+
+        let t = Table::concat(tables(), Align::ByName, ColumnSet::Intersect);
+        assert_eq!(
+            t.into_raw(),
+            vec![
+                vec![Obj("a")],
+                vec![n(1)],
+                vec![n(3)],
+                vec![n(6)],
+                vec![n(7)]
+            ]
+        );
+
+        // repeated headers match by occurrence, headerless tables by position
+        let mut x = Table::from(vec![vec![n(10), n(11), n(12)]]);
+        x.set_header(false);
+        let t = Table::concat(
+            vec![
+                Table::from(vec![vec![Obj("a"), Obj("a")], vec![n(1), n(2)]]),
+                Table::from(vec![
+                    vec![Obj("b"), Obj("a"), Obj("a")],
+                    vec![n(3), n(4), n(5)],
+                ]),
+                x,
+            ],
+            Align::ByName,
+            ColumnSet::Union,
+        );
+        assert_eq!(
+            t.into_raw(),
+            vec![
+                vec![Obj("a"), Obj("a"), Obj("b")],
+                vec![n(1), n(2), Nil],
+                vec![n(4), n(5), n(3)],
+                vec![n(10), n(11), n(12)],
+            ]
+        );
+    }
+
+    #[test]
+    fn concat_by_position() {
+        let t = Table::concat(tables(), Align::ByPosition, ColumnSet::Union);
+        assert_eq!(
+            t.into_raw(),
+            vec![
+                vec![Obj("a"), Obj("b"), Obj("c")],
+                vec![n(1), n(2), Nil],
+                vec![n(3), n(4), Nil],
+                vec![n(5), n(6), Nil],
+                vec![n(7), n(8), n(9)],
+            ]
+        );
+
+        let t = Table::concat(tables(), Align::ByPosition, ColumnSet::Intersect);
+        assert_eq!(t.cols_len(), 2);
+        assert_eq!(t.rows_len(), 5);
+
+        let t = Table::<()>::concat(vec![], Align::ByName, ColumnSet::Union);
+        assert_eq!(t, Table::new());
+    }
+
+    #[test]
+    fn hstack_tables() {
+        let mut t = tables().remove(0);
+        t.hstack(Table::from(vec![vec![Obj("c")], vec![n(5)], vec![n(6)]]));
+        assert_eq!(t.cols_len(), 3);
+        assert_eq!(
+            t.into_raw(),
+            vec![
+                vec![Obj("a"), Obj("b"), Obj("c")],
+                vec![n(1), n(2), n(5)],
+                vec![n(3), n(4), n(6)],
+            ]
+        );
+
+        let mut t = tables().remove(0);
+        let mut x = Table::from(vec![vec![n(5)], vec![n(6)]]);
+        x.set_header(false);
+        t.hstack(x);
+        assert_eq!(t.row(0).unwrap().nth(2), Some(&Nil));
+        assert_eq!(t.row(2).unwrap().nth(2), Some(&n(6)));
+
+        let mut t = tables().remove(0);
+        t.set_header(false);
+        t.hstack(Table::from(vec![
+            vec![Obj("c")],
+            vec![n(5)],
+            vec![n(6)],
+            vec![n(7)],
+        ]));
+        assert_eq!(t.row(0).unwrap().nth(2), Some(&n(5)));
+    }
+
+    #[test]
+    fn hstack_mismatch() {
+        let mut t = tables().remove(0);
+        let other = tables().remove(1);
+        let rows = other.rows_len() - 1;
+        assert_eq!(
+            t.try_hstack(other).err(),
+            Some(TableError::RowsMismatch {
+                rows: 2,
+                other: rows
+            })
+        );
+        assert_eq!(t, tables().remove(0));
+        assert_eq!(
+            TableError::RowsMismatch { rows: 2, other: 3 }.to_string(),
+            "data rows 2 do not match the data rows 3 of the stacked table"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn hstack_panic() {
+        let mut t = tables().remove(0);
+        t.hstack(tables().remove(1));
+    }
+}
@@ -0,0 +1,291 @@
+use super::*;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+
+/// Which of a set of duplicate rows to keep, see [`Table::dedup_by`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Keep {
+    /// Keep the first occurrence.
+    First,
+    /// Keep the last occurrence.
+    Last,
+    /// Keep none of the duplicated rows.
+    None,
+}
+
+impl<T: Hash + Eq + Sync> Table<T> {
+    /// The row indices of _data_ rows which are duplicates, comparing the entries in `cols`.
+    ///
+    /// The rows reported are those which [`Table::dedup_by`] would remove with the same `keep`. If
+    /// `cols` is empty, all columns are compared. Indices are in ascending order.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let mut r: Table<&str> = Table::new();
+    /// r.add_rows(
+    ///     vec![
+    ///         vec![Obj("id"), Obj("value")].into_iter(),
+    ///         vec![Num(1.into()), Obj("a")].into_iter(),
+    ///         vec![Num(2.into()), Obj("b")].into_iter(),
+    ///         vec![Num(1.into()), Obj("c")].into_iter(),
+    ///     ].into_iter()
+    /// );
+    ///
+    /// assert_eq!(r.duplicated(&[0], Keep::First), vec![3]);
+    /// assert_eq!(r.duplicated(&[0], Keep::Last), vec![1]);
+    /// assert_eq!(r.duplicated(&[0], Keep::None), vec![1, 3]);
+    /// assert_eq!(r.duplicated(&[], Keep::First), Vec::<usize>::new());
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if any column is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// The entries of each row are hashed in parallel.
+    pub fn duplicated(&self, cols: &[usize], keep: Keep) -> Vec<usize> {
+        or_panic(self.try_duplicated(cols, keep))
+    }
+
+    fn try_duplicated(&self, cols: &[usize], keep: Keep) -> Result<Vec<usize>, TableError> {
+        let s = if self.header { 1 } else { 0 };
+        let mut dups = self
+            .dup_groups(cols)?
+            .into_iter()
+            .filter(|g| g.len() > 1)
+            .flat_map(|mut g| {
+                match keep {
+                    Keep::First => drop(g.remove(0)),
+                    Keep::Last => drop(g.pop()),
+                    Keep::None => (),
+                }
+                g
+            })
+            .map(|i| i + s)
+            .collect::<Vec<_>>();
+        dups.sort_unstable();
+        Ok(dups)
+    }
+
+    /// Remove duplicate _data_ rows, comparing the entries in `cols`.
+    ///
+    /// The order of the remaining rows is kept. If `cols` is empty, all columns are compared.
+    ///
+    /// # Panics
+    /// Panics if any column is outside the columns bounds. See [`Table::try_dedup_by`].
+    ///
+    /// # Parallelisation
+    /// The entries of each row are hashed in parallel.
+    pub fn dedup_by(&mut self, cols: &[usize], keep: Keep) -> &mut Self {
+        or_panic(self.try_dedup_by(cols, keep))
+    }
+
+    /// Remove duplicate _data_ rows, comparing the entries in `cols`. See [`Table::dedup_by`].
+    ///
+    /// Returns an error if any column is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// The entries of each row are hashed in parallel.
+    pub fn try_dedup_by(&mut self, cols: &[usize], keep: Keep) -> Result<&mut Self, TableError> {
+        let mut remove = vec![false; self.rows_len()];
+        for i in self.try_duplicated(cols, keep)? {
+            remove[i] = true;
+        }
+        self.retain_rows(|i, _| !remove[i]);
+        Ok(self)
+    }
+
+    /// A new table of the distinct rows of the columns `cols`, in order of first occurrence.
+    ///
+    /// The header row is retained. If `cols` is empty, all columns are used.
+    ///
+    /// # Panics
+    /// Panics if any column is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// The entries of each row are hashed in parallel.
+    pub fn distinct(&self, cols: &[usize]) -> Table<T>
+    where
+        T: Clone,
+    {
+        let cols = or_panic(self.dedup_cols(cols));
+        let s = if self.header { 1 } else { 0 };
+        let mut firsts = or_panic(self.dup_groups(&cols))
+            .into_iter()
+            .map(|g| g[0] + s)
+            .collect::<Vec<_>>();
+        firsts.sort_unstable();
+
+        let mut table = Table::new();
+        table.header = self.header;
+        table.add_rows(
+            self.data
+                .get(..s)
+                .unwrap_or_default()
+                .iter()
+                .chain(firsts.into_iter().map(|i| &self.data[i]))
+                .map(|row| cols.iter().map(move |&c| row[c].clone())),
+        );
+        table
+    }
+
+    /// The columns to compare, checking bounds and defaulting to all columns.
+    fn dedup_cols(&self, cols: &[usize]) -> Result<Vec<usize>, TableError> {
+        for &c in cols {
+            TableError::check_col(c, self.cols_len())?;
+        }
+        Ok(match cols.is_empty() {
+            true => (0..self.cols_len()).collect(),
+            false => cols.to_vec(),
+        })
+    }
+
+    /// Groups of _data_ row indices (excluding the header offset) with equal entries in `cols`,
+    /// each in ascending order.
+    fn dup_groups(&self, cols: &[usize]) -> Result<Vec<Vec<usize>>, TableError> {
+        let cols = self.dedup_cols(cols)?;
+        let s = if self.header { 1 } else { 0 };
+        let data = self.data.get(s..).unwrap_or_default();
+        let key = |i: usize| cols.iter().map(move |&c| &data[i][c]);
+
+        let hashes = data
+            .par_iter()
+            .map(|row| {
+                let mut h = DefaultHasher::new();
+                cols.iter().for_each(|&c| row[c].hash(&mut h));
+                h.finish()
+            })
+            .collect::<Vec<_>>();
+
+        // hash -> group indices
+        let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for (i, h) in hashes.into_iter().enumerate() {
+            let bucket = buckets.entry(h).or_default();
+            match bucket.iter().find(|&&g| key(groups[g][0]).eq(key(i))) {
+                Some(&g) => groups[g].push(i),
+                None => {
+                    bucket.push(groups.len());
+                    groups.push(vec![i]);
+                }
+            }
+        }
+
+        Ok(groups)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n(x: i32) -> Entry<&'static str> {
+        Num(x.into())
+    }
+
+    fn table() -> Table<&'static str> {
+        Table::from(vec![
+            vec![Obj("id"), Obj("v")],
+            vec![n(1), Obj("a")],
+            vec![n(2), Obj("b")],
+            vec![n(1), Obj("a")],
+            vec![n(1), Obj("c")],
+            vec![Nil, Nil],
+            vec![Nil, Nil],
+        ])
+    }
+
+    #[test]
+    fn duplicated_rows() {
+        let t = table();
+        assert_eq!(t.duplicated(&[], Keep::First), vec![3, 6]);
+        assert_eq!(t.duplicated(&[], Keep::Last), vec![1, 5]);
+        assert_eq!(t.duplicated(&[], Keep::None), vec![1, 3, 5, 6]);
+        assert_eq!(t.duplicated(&[0], Keep::First), vec![3, 4, 6]);
+        assert_eq!(t.duplicated(&[1, 0], Keep::None), vec![1, 3, 5, 6]);
+
+        let mut t = table();
+        t.set_header(false);
+        assert_eq!(t.duplicated(&[0], Keep::First), vec![3, 4, 6]);
+        t.add_row(vec![Obj("id"), Obj("v")].into_iter());
+        assert_eq!(t.duplicated(&[], Keep::Last), vec![0, 1, 5]);
+    }
+
+    #[test]
+    fn dedup() {
+        let mut t = table();
+        t.dedup_by(&[], Keep::First);
+        assert_eq!(
+            t.into_raw(),
+            vec![
+                vec![Obj("id"), Obj("v")],
+                vec![n(1), Obj("a")],
+                vec![n(2), Obj("b")],
+                vec![n(1), Obj("c")],
+                vec![Nil, Nil],
+            ]
+        );
+
+        let mut t = table();
+        t.dedup_by(&[0], Keep::Last);
+        assert_eq!(
+            t.into_raw(),
+            vec![
+                vec![Obj("id"), Obj("v")],
+                vec![n(2), Obj("b")],
+                vec![n(1), Obj("c")],
+                vec![Nil, Nil],
+            ]
+        );
+
+        let mut t = table();
+        t.dedup_by(&[0], Keep::None);
+        assert_eq!(
+            t.into_raw(),
+            vec![vec![Obj("id"), Obj("v")], vec![n(2), Obj("b")]]
+        );
+    }
+
+    #[test]
+    fn distinct_cols() {
+        let t = table();
+        let d = t.distinct(&[0]);
+        assert!(d.header);
+        assert_eq!(
+            d.into_raw(),
+            vec![vec![Obj("id")], vec![n(1)], vec![n(2)], vec![Nil]]
+        );
+
+        let d = t.distinct(&[1, 0]);
+        assert_eq!(
+            d.into_raw(),
+            vec![
+                vec![Obj("v"), Obj("id")],
+                vec![Obj("a"), n(1)],
+                vec![Obj("b"), n(2)],
+                vec![Obj("c"), n(1)],
+                vec![Nil, Nil],
+            ]
+        );
+
+        assert_eq!(Table::<()>::new().distinct(&[]), Table::new());
+    }
+
+    #[test]
+    fn dedup_err() {
+        let mut t = table();
+        assert_eq!(
+            t.try_dedup_by(&[0, 2], Keep::First).err(),
+            Some(TableError::ColOutOfBounds { index: 2, cols: 2 })
+        );
+        assert_eq!(t, table());
+    }
+
+    #[test]
+    #[should_panic]
+    fn dedup_panic() {
+        table().dedup_by(&[2], Keep::First);
+    }
+}
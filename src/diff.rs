@@ -0,0 +1,434 @@
+use super::*;
+use std::{collections::HashMap, fmt, hash::Hash};
+
+/// The differences between two tables, see [`Table::diff`].
+///
+/// Rows are table row indices. Deleted rows and changed cells index the original table, inserted
+/// rows index the other table.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TableDiff<T> {
+    /// Rows only in the other table, in row order.
+    pub inserted: Vec<RowChange<T>>,
+    /// Rows only in the original table, in row order.
+    pub deleted: Vec<RowChange<T>>,
+    /// Cells which differ between paired rows, in row then column order.
+    pub changed: Vec<CellChange<T>>,
+    /// The header row of the original table, used for rendering.
+    header: Option<Vec<Entry<T>>>,
+    /// The number of columns of the other table.
+    cols: usize,
+}
+
+/// A row present in only one table, see [`TableDiff`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RowChange<T> {
+    /// The row index.
+    pub row: usize,
+    /// The row entries.
+    pub entries: Vec<Entry<T>>,
+}
+
+/// A cell which differs between paired rows, see [`TableDiff`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CellChange<T> {
+    /// The row index in the original table.
+    pub row: usize,
+    /// The row index in the other table.
+    pub other_row: usize,
+    /// The column index.
+    pub col: usize,
+    /// The entry in the original table.
+    pub old: Entry<T>,
+    /// The entry in the other table.
+    pub new: Entry<T>,
+}
+
+impl<T> TableDiff<T> {
+    /// There are no differences.
+    pub fn is_empty(&self) -> bool {
+        self.inserted.is_empty() && self.deleted.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl<T: Hash + Eq + Clone + Send + Sync> Table<T> {
+    /// The differences between the _data_ rows of this table and `other`.
+    ///
+    /// Rows are paired by the entries in `key_cols`, or by position if `key_cols` is empty.
+    /// Duplicate keys are paired in order of occurrence, and [`Nil`] keys pair with each other.
+    /// Unpaired rows are inserted or deleted, and paired rows are compared cell by cell, with
+    /// entries beyond a row's end comparing as [`Nil`]. Header rows are not compared.
+    ///
+    /// The diff renders as a report, and can be applied to this table with [`Table::apply_diff`].
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let yesterday: Table<&str> = Table::from(vec![
+    ///     vec![Obj("id"), Obj("price")],
+    ///     vec![Obj("a"), Num(326.into())],
+    ///     vec![Obj("b"), Num(334.into())],
+    /// ]);
+    /// let today: Table<&str> = Table::from(vec![
+    ///     vec![Obj("id"), Obj("price")],
+    ///     vec![Obj("c"), Num(335.into())],
+    ///     vec![Obj("a"), Num(327.into())],
+    /// ]);
+    ///
+    /// let diff = yesterday.diff(&today, &[0]);
+    /// assert_eq!(diff.to_string(), "\
+    /// 1 inserted, 1 deleted, 1 changed
+    /// + [1] c, 335
+    /// - [2] b, 334
+    /// ~ [1] price: 326 -> 327
+    /// ");
+    ///
+    /// let mut patched = yesterday.clone();
+    /// patched.apply_diff(&diff);
+    /// assert!(patched.diff(&today, &[0]).is_empty());
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if any key column is outside the columns bounds of either table.
+    ///
+    /// # Parallelisation
+    /// Keys are hashed and paired rows are compared in parallel.
+    pub fn diff(&self, other: &Table<T>, key_cols: &[usize]) -> TableDiff<T> {
+        for &c in key_cols {
+            or_panic(TableError::check_col(c, self.cols_len()));
+            or_panic(TableError::check_col(c, other.cols_len()));
+        }
+
+        let s = if self.header { 1 } else { 0 };
+        let so = if other.header { 1 } else { 0 };
+        let data = self.data.get(s..).unwrap_or_default();
+        let odata = other.data.get(so..).unwrap_or_default();
+
+        // pairs of data row indices
+        let pairs = if key_cols.is_empty() {
+            (0..min(data.len(), odata.len())).map(|i| (i, i)).collect()
+        } else {
+            pair_by_key(data, odata, key_cols)
+        };
+
+        let mut paired = vec![false; data.len()];
+        let mut opaired = vec![false; odata.len()];
+        for &(i, j) in &pairs {
+            paired[i] = true;
+            opaired[j] = true;
+        }
+
+        let unpaired = |data: &[Vec<Entry<T>>], paired: Vec<bool>, s| {
+            let mut v = Vec::new();
+            v.par_extend(
+                data.par_iter()
+                    .zip(paired)
+                    .enumerate()
+                    .filter(|(_, (_, p))| !p)
+                    .map(|(i, (row, _))| RowChange {
+                        row: i + s,
+                        entries: row.clone(),
+                    }),
+            );
+            v
+        };
+
+        let cols = max(self.cols_len(), other.cols_len());
+        let mut changed = Vec::new();
+        changed.par_extend(pairs.par_iter().flat_map_iter(|&(i, j)| {
+            let (a, b) = (&data[i], &odata[j]);
+            (0..cols).filter_map(move |c| {
+                let nil = Nil;
+                let old = a.get(c).unwrap_or(&nil);
+                let new = b.get(c).unwrap_or(&nil);
+                (old != new).then(|| CellChange {
+                    row: i + s,
+                    other_row: j + so,
+                    col: c,
+                    old: old.clone(),
+                    new: new.clone(),
+                })
+            })
+        }));
+
+        TableDiff {
+            inserted: unpaired(odata, opaired, so),
+            deleted: unpaired(data, paired, s),
+            changed,
+            header: self.data.get(..s).map(|h| h.concat()),
+            cols: other.cols_len(),
+        }
+    }
+
+    /// Apply `diff` to this table, which should be the table the diff was taken from.
+    ///
+    /// Changed cells are set, deleted rows removed, and inserted rows appended in order. The table
+    /// is widened with [`Nil`] columns if the other table was wider.
+    ///
+    /// # Panics
+    /// Panics if any row or column of the diff is outside the table bounds. See
+    /// [`Table::try_apply_diff`].
+    pub fn apply_diff(&mut self, diff: &TableDiff<T>) -> &mut Self {
+        or_panic(self.try_apply_diff(diff))
+    }
+
+    /// Apply `diff` to this table. See [`Table::apply_diff`].
+    ///
+    /// Returns an error if any row or column of the diff is outside the table bounds, in which
+    /// case the table is not modified.
+    pub fn try_apply_diff(&mut self, diff: &TableDiff<T>) -> Result<&mut Self, TableError> {
+        let (rows, cols) = (self.rows_len(), max(self.cols_len(), diff.cols));
+        for c in &diff.changed {
+            TableError::check_row(c.row, rows)?;
+            TableError::check_col(c.col, cols)?;
+        }
+        for d in &diff.deleted {
+            TableError::check_row(d.row, rows)?;
+        }
+
+        if cols > self.cols {
+            self.cols = cols;
+            self.resize_cols();
+        }
+        for c in &diff.changed {
+            self.data[c.row][c.col] = c.new.clone();
+        }
+        let mut remove = vec![false; rows];
+        for d in &diff.deleted {
+            remove[d.row] = true;
+        }
+        self.retain_rows(|i, _| !remove[i]);
+        self.add_rows(diff.inserted.iter().map(|r| r.entries.iter().cloned()));
+        Ok(self)
+    }
+}
+
+/// Pair data rows with equal keys, in order of occurrence.
+fn pair_by_key<'a, T: Hash + Eq + Sync>(
+    data: &'a [Vec<Entry<T>>],
+    odata: &'a [Vec<Entry<T>>],
+    key_cols: &[usize],
+) -> Vec<(usize, usize)> {
+    let key = |row: &'a Vec<Entry<T>>| key_cols.iter().map(|&c| &row[c]).collect::<Vec<_>>();
+
+    let omap = odata
+        .par_iter()
+        .enumerate()
+        .fold(HashMap::new, |mut map: HashMap<_, Vec<_>>, (j, row)| {
+            map.entry(key(row)).or_default().push(j);
+            map
+        })
+        .reduce(HashMap::new, |mut a, b| {
+            // b follows a in row order
+            for (k, rows) in b {
+                a.entry(k).or_default().extend(rows);
+            }
+            a
+        });
+    let mut omap = omap
+        .into_iter()
+        .map(|(k, rows)| (k, rows.into_iter()))
+        .collect::<HashMap<_, _>>();
+
+    let keys = data.par_iter().map(key).collect::<Vec<_>>();
+    keys.into_iter()
+        .enumerate()
+        .filter_map(|(i, k)| omap.get_mut(&k).and_then(Iterator::next).map(|j| (i, j)))
+        .collect()
+}
+
+impl<T: AsRef<str>> fmt::Display for TableDiff<T> {
+    /// A line per inserted row (`+`), deleted row (`-`) and changed cell (`~`), following a
+    /// summary line. Cells are labelled by the header if the original table has one.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{} inserted, {} deleted, {} changed",
+            self.inserted.len(),
+            self.deleted.len(),
+            self.changed.len()
+        )?;
+
+        let row = |f: &mut fmt::Formatter, sign, r: &RowChange<T>| {
+            write!(f, "{} [{}] ", sign, r.row)?;
+            for (i, e) in r.entries.iter().enumerate() {
+                let sep = if i == 0 { "" } else { ", " };
+                write!(f, "{}{}", sep, e.as_str())?;
+            }
+            writeln!(f)
+        };
+        for r in &self.inserted {
+            row(f, '+', r)?;
+        }
+        for r in &self.deleted {
+            row(f, '-', r)?;
+        }
+
+        for c in &self.changed {
+            write!(f, "~ [{}] ", c.row)?;
+            match self.header.as_ref().and_then(|h| h.get(c.col)) {
+                Some(name) => write!(f, "{}", name.as_str())?,
+                None => write!(f, "{}", c.col)?,
+            }
+            writeln!(f, ": {} -> {}", c.old.as_str(), c.new.as_str())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n(x: i32) -> Entry<&'static str> {
+        Num(x.into())
+    }
+
+    fn old() -> Table<&'static str> {
+        Table::from(vec![
+            vec![Obj("id"), Obj("qty"), Obj("desk")],
+            vec![Obj("a"), n(1), Obj("x")],
+            vec![Obj("b"), n(2), Obj("y")],
+            vec![Obj("a"), n(3), Obj("x")],
+            vec![Nil, n(4), Obj("z")],
+        ])
+    }
+
+    fn new() -> Table<&'static str> {
+        Table::from(vec![
+            vec![Obj("id"), Obj("qty"), Obj("desk")],
+            vec![Nil, n(4), Obj("z")],
+            vec![Obj("a"), n(1), Obj("x")],
+            vec![Obj("c"), n(5), Obj("y")],
+            vec![Obj("a"), n(3), Obj("w")],
+            vec![Obj("a"), n(6), Nil],
+        ])
+    }
+
+    #[test]
+    fn keyed() {
+        let d = old().diff(&new(), &[0]);
+        assert_eq!(
+            d.inserted,
+            vec![
+                RowChange {
+                    row: 3,
+                    entries: vec![Obj("c"), n(5), Obj("y")]
+                },
+                RowChange {
+                    row: 5,
+                    entries: vec![Obj("a"), n(6), Nil]
+                },
+            ]
+        );
+        assert_eq!(
+            d.deleted,
+            vec![RowChange {
+                row: 2,
+                entries: vec![Obj("b"), n(2), Obj("y")]
+            }]
+        );
+        // duplicate keys pair in order of occurrence
+        assert_eq!(
+            d.changed,
+            vec![CellChange {
+                row: 3,
+                other_row: 4,
+                col: 2,
+                old: Obj("x"),
+                new: Obj("w")
+            }]
+        );
+
+        // multiple key columns
+        let d = old().diff(&new(), &[0, 1]);
+        assert_eq!(d.inserted.len(), 2);
+        assert_eq!(d.deleted.len(), 1);
+        assert_eq!(d.changed.len(), 1);
+    }
+
+    #[test]
+    fn positional() {
+        let mut a = old();
+        a.set_header(false);
+        let mut b = Table::from(vec![
+            vec![Obj("id"), Obj("qty")],
+            vec![Obj("a"), n(2)],
+            vec![Obj("b"), n(2), Obj("y")],
+        ]);
+        b.set_header(false);
+        let d = a.diff(&b, &[]);
+        assert!(d.inserted.is_empty());
+        assert_eq!(
+            d.deleted.iter().map(|r| r.row).collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+        assert_eq!(
+            d.changed
+                .iter()
+                .map(|c| (c.row, c.col, c.new.clone()))
+                .collect::<Vec<_>>(),
+            vec![(0, 2, Nil), (1, 1, n(2)), (1, 2, Nil)]
+        );
+        assert!(old().diff(&old(), &[]).is_empty());
+        assert!(old().diff(&old(), &[1, 0]).is_empty());
+    }
+
+    #[test]
+    fn apply() {
+        for keys in [&[0][..], &[0, 2], &[]] {
+            let mut t = old();
+            let d = t.diff(&new(), keys);
+            t.apply_diff(&d);
+            assert!(t.diff(&new(), keys).is_empty());
+            assert_eq!(t.rows_len(), new().rows_len());
+        }
+
+        // widening
+        let mut narrow = Table::from(vec![vec![Obj("k")], vec![n(1)], vec![n(2)]]);
+        let wide = Table::from(vec![vec![Obj("k"), Nil], vec![n(1), n(3)], vec![n(2), Nil]]);
+        let d = narrow.diff(&wide, &[0]);
+        narrow.apply_diff(&d);
+        assert_eq!(narrow.into_raw(), wide.into_raw());
+
+        // out of bounds diffs do not modify the table
+        let mut t = old();
+        let d = t.diff(&new(), &[0]);
+        t.remove_row(4).remove_row(3);
+        assert_eq!(
+            t.try_apply_diff(&d).err(),
+            Some(TableError::RowOutOfBounds { index: 3, rows: 3 })
+        );
+        assert_eq!(t.into_raw(), old().into_raw()[..3].to_vec());
+    }
+
+    #[test]
+    fn report() {
+        let mut t = old();
+        assert_eq!(
+            t.diff(&new(), &[0]).to_string(),
+            "\
+2 inserted, 1 deleted, 1 changed
++ [3] c, 5, y
++ [5] a, 6, -
+- [2] b, 2, y
+~ [3] desk: x -> w
+"
+        );
+
+        t.set_header(false);
+        let mut u = old();
+        u.set_header(false);
+        u[(0, 1)] = Obj("count");
+        assert_eq!(
+            t.diff(&u, &[]).to_string(),
+            "0 inserted, 0 deleted, 1 changed\n~ [0] 1: qty -> count\n"
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn diff_panic() {
+        old().diff(&Table::from(vec![vec![n(1)]]), &[1]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::{collate, num, time};
 use ::divvy::Str;
 use ::kserd::*;
 use std::{borrow::Cow, cmp::Ordering};
@@ -6,7 +7,7 @@ use Entry::*;
 /// A table cell.
 ///
 /// An entry is mostly of arbitary type, distinguishing between empty (`Nil`) entries, numeric
-/// entries (`Num`), and object entries `T`.
+/// entries (`Num`), booleans, dates and times (see [`time`](crate::time)), and object entries `T`.
 ///
 /// An entry exhibits total equality if `T: Eq`, and has partial ordering. Ordering of the same
 /// variants will work, but if the variants are different, no ordering occurs.
@@ -27,9 +28,17 @@ use Entry::*;
 pub enum Entry<T> {
     /// An empty entry.
     Nil,
+    /// A boolean entry.
+    Bool(bool),
     /// A numeric entry. [`Number`] is used to acheive [`Ord`] and [`Eq`] across integers and
     /// floats.
     Num(Number),
+    /// A calendar date.
+    Date(time::Date),
+    /// An instant in time, in UTC.
+    DateTime(time::DateTime),
+    /// A signed span of time.
+    Duration(time::Duration),
     /// An arbitary object type.
     Obj(T),
 }
@@ -72,15 +81,120 @@ impl<T> Entry<T> {
         }
     }
 
+    /// Retreives value if Entry is [`Entry::Bool`] variant.
+    pub fn bool(&self) -> Option<bool> {
+        match self {
+            Entry::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    /// Retreives value if Entry is [`Entry::Date`] variant.
+    pub fn date(&self) -> Option<time::Date> {
+        match self {
+            Entry::Date(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    /// Retreives value if Entry is [`Entry::DateTime`] variant.
+    pub fn datetime(&self) -> Option<time::DateTime> {
+        match self {
+            Entry::DateTime(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    /// Retreives value if Entry is [`Entry::Duration`] variant.
+    pub fn duration(&self) -> Option<time::Duration> {
+        match self {
+            Entry::Duration(d) => Some(*d),
+            _ => None,
+        }
+    }
+
+    /// The numeric value as a float, if Entry is [`Entry::Num`] variant.
+    ///
+    /// Entries also convert with [`TryFrom`] into [`Number`], `f64`, `i64`, `bool`, and the
+    /// [`time`](crate::time) types, returning the entry as the error if it does not match.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// assert_eq!(Entry::<()>::Num(1.5.into()).as_f64(), Some(1.5));
+    ///
+    /// let x: f64 = Entry::<()>::Num(1.5.into()).try_into().unwrap();
+    /// assert_eq!(x, 1.5);
+    /// let x: Result<bool, _> = Entry::Obj("true").try_into();
+    /// assert_eq!(x, Err(Entry::Obj("true")));
+    /// ```
+    pub fn as_f64(&self) -> Option<f64> {
+        self.num().map(|n| n.as_f64())
+    }
+
+    /// The numeric value as an integer, if Entry is [`Entry::Num`] variant and the number is a
+    /// whole number within the `i64` range.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// assert_eq!(Entry::<()>::Num(3.into()).as_i64(), Some(3));
+    /// assert_eq!(Entry::<()>::Num(3.0.into()).as_i64(), Some(3));
+    /// assert_eq!(Entry::<()>::Num(3.5.into()).as_i64(), None);
+    /// assert_eq!(Entry::<()>::Nil.as_i64(), None);
+    /// ```
+    pub fn as_i64(&self) -> Option<i64> {
+        let n = self.num()?;
+        match num::int(n) {
+            Some(x) => i64::try_from(x).ok(),
+            None => {
+                let x = n.as_f64();
+                let whole = x.fract() == 0.0 && x >= i64::MIN as f64 && x < i64::MAX as f64;
+                whole.then_some(x as i64)
+            }
+        }
+    }
+
+    /// Converts from `&Entry<T>` to `Entry<&T>`.
+    pub fn as_ref(&self) -> Entry<&T> {
+        match self {
+            Nil => Nil,
+            Bool(b) => Bool(*b),
+            Num(n) => Num(*n),
+            Date(d) => Date(*d),
+            DateTime(d) => DateTime(*d),
+            Duration(d) => Duration(*d),
+            Obj(t) => Obj(t),
+        }
+    }
+
+    /// Maps an `Entry<T>` to `Entry<U>` by applying `f` to an [`Entry::Obj`] value, leaving other
+    /// variants the same.
+    pub fn map_obj<U, F>(self, f: F) -> Entry<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        match self {
+            Nil => Nil,
+            Bool(b) => Bool(b),
+            Num(n) => Num(n),
+            Date(d) => Date(d),
+            DateTime(d) => DateTime(d),
+            Duration(d) => Duration(d),
+            Obj(t) => Obj(f(t)),
+        }
+    }
+
     /// Represent this entry as a _borrowed_ string.
     ///
     /// If `T` implements [`AsRef`]`<str>` then `Entry` can be represented as a string, without
-    /// allocation. If `Entry` is numeric, allocation is required and `Cow::Owned` is returned.
+    /// allocation. If `Entry` is numeric or temporal, allocation is required and `Cow::Owned` is
+    /// returned. Temporal entries are formatted as ISO-8601.
     ///
     /// ```rust
     /// # use ::table::Entry;
     /// assert_eq!(Entry::<String>::Nil.as_str(), "-");
+    /// assert_eq!(Entry::<String>::Bool(true).as_str(), "true");
     /// assert_eq!(Entry::<String>::Num(3.14.into()).as_str(), "3.14");
+    /// assert_eq!(Entry::<String>::Date("2020-01-31".parse().unwrap()).as_str(), "2020-01-31");
     /// assert_eq!(Entry::<String>::Obj("what".into()).as_str(), "what");
     /// ```
     pub fn as_str(&self) -> Cow<'_, str>
@@ -89,19 +203,170 @@ impl<T> Entry<T> {
     {
         match self {
             Nil => Cow::Borrowed("-"),
+            Bool(true) => Cow::Borrowed("true"),
+            Bool(false) => Cow::Borrowed("false"),
             Num(n) => Cow::Owned(n.to_string()),
+            Date(d) => Cow::Owned(d.to_string()),
+            DateTime(d) => Cow::Owned(d.to_string()),
+            Duration(d) => Cow::Owned(d.to_string()),
             Obj(o) => Cow::Borrowed(o.as_ref()),
         }
     }
 }
 
+impl<T: Ord> Entry<T> {
+    /// Compare entries with a _total_ ordering, using the default [`VariantOrder`] of
+    /// `Nil < Bool < Num < Date < DateTime < Duration < Obj`.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use std::cmp::Ordering;
+    /// let lhs: Entry<&str> = Entry::Nil;
+    /// assert_eq!(lhs.total_cmp(&Entry::Num(1.into())), Ordering::Less);
+    /// assert_eq!(Entry::Num(1.into()).total_cmp(&Entry::Obj("a")), Ordering::Less);
+    /// assert_eq!(Entry::Obj("b").total_cmp(&Entry::Obj("a")), Ordering::Greater);
+    /// ```
+    pub fn total_cmp(&self, rhs: &Self) -> Ordering {
+        self.total_cmp_with(rhs, VariantOrder::default())
+    }
+
+    /// Compare entries with a _total_ ordering, ordering differing variants using `order`.
+    pub fn total_cmp_with(&self, rhs: &Self, order: VariantOrder) -> Ordering {
+        self.cmp_by(rhs, order, Ord::cmp)
+    }
+}
+
+impl<T> Entry<T> {
+    /// Compare entries with a _total_ ordering, ordering differing variants using `order` and
+    /// `Obj` variants using `f`.
+    ///
+    /// This is the building block for custom orderings over `T` which still need to define
+    /// ordering between variants.
+    pub fn cmp_by<F>(&self, rhs: &Self, order: VariantOrder, f: F) -> Ordering
+    where
+        F: FnOnce(&T, &T) -> Ordering,
+    {
+        match (self, rhs) {
+            (Nil, Nil) => Ordering::Equal,
+            (Bool(lhs), Bool(rhs)) => lhs.cmp(rhs),
+            (Num(lhs), Num(rhs)) => lhs.cmp(rhs),
+            (Date(lhs), Date(rhs)) => lhs.cmp(rhs),
+            (DateTime(lhs), DateTime(rhs)) => lhs.cmp(rhs),
+            (Duration(lhs), Duration(rhs)) => lhs.cmp(rhs),
+            (Obj(lhs), Obj(rhs)) => f(lhs, rhs),
+            (lhs, rhs) => order.rank(lhs).cmp(&order.rank(rhs)),
+        }
+    }
+}
+
+/// String orderings, which can be passed directly to [`Table::sort`](crate::Table::sort) or
+/// [`Table::sort_by_keys_with`](crate::Table::sort_by_keys_with).
+///
+/// Differing variants are ordered using the default [`VariantOrder`].
+///
+/// ```rust
+/// # use table::*;
+/// use Entry::*;
+/// let mut r: Table<&str> = Table::new();
+/// r.set_header(false);
+/// r.add_col(vec![Obj("file10"), Obj("File2"), Obj("file1")].into_iter());
+///
+/// r.sort(0, Entry::natural_cmp);
+/// assert_eq!(r.col(0).unwrap().collect::<Vec<_>>(), [&Obj("File2"), &Obj("file1"), &Obj("file10")]);
+///
+/// r.sort(0, Entry::case_insensitive_cmp);
+/// assert_eq!(r.col(0).unwrap().collect::<Vec<_>>(), [&Obj("file1"), &Obj("file10"), &Obj("File2")]);
+/// ```
+impl<T: AsRef<str>> Entry<T> {
+    /// Natural, numeric aware ordering of `Obj` entries, such that `file2 < file10`.
+    pub fn natural_cmp(&self, rhs: &Self) -> Ordering {
+        self.cmp_by(rhs, VariantOrder::default(), |a, b| {
+            collate::natural(a.as_ref(), b.as_ref())
+        })
+    }
+
+    /// Case insensitive ordering of `Obj` entries, such that `apple < Zebra`.
+    pub fn case_insensitive_cmp(&self, rhs: &Self) -> Ordering {
+        self.cmp_by(rhs, VariantOrder::default(), |a, b| {
+            collate::case_insensitive(a.as_ref(), b.as_ref())
+        })
+    }
+
+    /// Locale independent collation of `Obj` entries, approximating the Unicode root collation.
+    ///
+    /// Strings are compared by base characters first, then diacritics, then case, such that
+    /// `arg < Ärger < zebra`. Diacritic folding only covers the Latin-1 Supplement and Latin
+    /// Extended-A blocks, along with combining diacritical marks; other characters, such as those
+    /// of Latin Extended-B or Vietnamese, are compared by code point.
+    pub fn collate_cmp(&self, rhs: &Self) -> Ordering {
+        self.cmp_by(rhs, VariantOrder::default(), |a, b| {
+            collate::collation(a.as_ref(), b.as_ref())
+        })
+    }
+}
+
+/// The relative ordering of [`Entry`] variants, used when a _total_ ordering is required.
+///
+/// Lower ranks are ordered first. The default is
+/// `Nil < Bool < Num < Date < DateTime < Duration < Obj`.
+///
+/// ```rust
+/// # use table::*;
+/// use std::cmp::Ordering;
+/// let objs_first = VariantOrder { obj: 0, nil: 7, ..VariantOrder::default() };
+/// let lhs: Entry<&str> = Entry::Obj("a");
+/// assert_eq!(lhs.total_cmp_with(&Entry::Num(1.into()), objs_first), Ordering::Less);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct VariantOrder {
+    /// Rank of the [`Entry::Nil`] variant.
+    pub nil: u8,
+    /// Rank of the [`Entry::Bool`] variant.
+    pub bool: u8,
+    /// Rank of the [`Entry::Num`] variant.
+    pub num: u8,
+    /// Rank of the [`Entry::Date`] variant.
+    pub date: u8,
+    /// Rank of the [`Entry::DateTime`] variant.
+    pub datetime: u8,
+    /// Rank of the [`Entry::Duration`] variant.
+    pub duration: u8,
+    /// Rank of the [`Entry::Obj`] variant.
+    pub obj: u8,
+}
+
+impl VariantOrder {
+    /// The rank of the entry's variant.
+    pub fn rank<T>(&self, entry: &Entry<T>) -> u8 {
+        match entry {
+            Nil => self.nil,
+            Bool(_) => self.bool,
+            Num(_) => self.num,
+            Date(_) => self.date,
+            DateTime(_) => self.datetime,
+            Duration(_) => self.duration,
+            Obj(_) => self.obj,
+        }
+    }
+}
+
+impl Default for VariantOrder {
+    fn default() -> Self {
+        Self {
+            nil: 0,
+            bool: 1,
+            num: 2,
+            date: 3,
+            datetime: 4,
+            duration: 5,
+            obj: 6,
+        }
+    }
+}
+
 impl<T: Copy> From<&Entry<T>> for Entry<T> {
     fn from(e: &Entry<T>) -> Self {
-        match e {
-            Nil => Nil,
-            Num(x) => Num(*x),
-            Obj(x) => Obj(*x),
-        }
+        e.as_ref().map_obj(|x| *x)
     }
 }
 
@@ -109,6 +374,7 @@ impl<'a> From<&Kserd<'a>> for Entry<Str> {
     fn from(kserd: &Kserd<'a>) -> Self {
         match &kserd.val {
             Value::Unit => Nil,
+            Value::Bool(b) => Bool(*b),
             Value::Num(n) => Num(*n),
             Value::Str(s) => Obj(Str::new(s.as_str())),
             _ => Obj(Str::new(kserd.as_str())),
@@ -125,11 +391,41 @@ impl<T: AsRef<str>> PartialEq<str> for Entry<T> {
     }
 }
 
+// Fallible conversions out of an entry, returning the entry as the error if it is not the
+// matching variant.
+macro_rules! try_from_entry {
+    ($($t:ty => $f:expr,)*) => {
+        $(
+            impl<T> TryFrom<Entry<T>> for $t {
+                type Error = Entry<T>;
+
+                fn try_from(e: Entry<T>) -> Result<Self, Entry<T>> {
+                    $f(&e).ok_or(e)
+                }
+            }
+        )*
+    };
+}
+
+try_from_entry! {
+    Number => Entry::num,
+    f64 => Entry::as_f64,
+    i64 => Entry::as_i64,
+    bool => Entry::bool,
+    time::Date => Entry::date,
+    time::DateTime => Entry::datetime,
+    time::Duration => Entry::duration,
+}
+
 impl<T: PartialOrd> PartialOrd for Entry<T> {
     fn partial_cmp(&self, rhs: &Entry<T>) -> Option<Ordering> {
         match (self, rhs) {
             (Nil, Nil) => Some(Ordering::Equal),
+            (Bool(lhs), Bool(rhs)) => lhs.partial_cmp(rhs),
             (Num(lhs), Num(rhs)) => lhs.partial_cmp(rhs),
+            (Date(lhs), Date(rhs)) => lhs.partial_cmp(rhs),
+            (DateTime(lhs), DateTime(rhs)) => lhs.partial_cmp(rhs),
+            (Duration(lhs), Duration(rhs)) => lhs.partial_cmp(rhs),
             (Obj(lhs), Obj(rhs)) => lhs.partial_cmp(rhs),
             _ => None,
         }
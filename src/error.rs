@@ -0,0 +1,83 @@
+use std::{error, fmt};
+
+/// An error from a fallible table operation.
+///
+/// Returned by the `try_*` variants of methods, such as [`Table::try_remove_row`], which panic
+/// with the same message otherwise.
+///
+/// [`Table::try_remove_row`]: crate::Table::try_remove_row
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TableError {
+    /// A row index is outside the rows bounds.
+    RowOutOfBounds {
+        /// The offending index.
+        index: usize,
+        /// The number of rows.
+        rows: usize,
+    },
+    /// A column index is outside the columns bounds.
+    ColOutOfBounds {
+        /// The offending index.
+        index: usize,
+        /// The number of columns.
+        cols: usize,
+    },
+    /// A column name does not match any header.
+    UnknownColumn(String),
+    /// The number of _data_ rows of two tables differ.
+    RowsMismatch {
+        /// The number of data rows of the table.
+        rows: usize,
+        /// The number of data rows of the other table.
+        other: usize,
+    },
+}
+
+impl TableError {
+    /// Check `index` is less than `rows`.
+    pub(crate) fn check_row(index: usize, rows: usize) -> Result<(), Self> {
+        if index < rows {
+            Ok(())
+        } else {
+            Err(TableError::RowOutOfBounds { index, rows })
+        }
+    }
+
+    /// Check `index` is less than `cols`.
+    pub(crate) fn check_col(index: usize, cols: usize) -> Result<(), Self> {
+        if index < cols {
+            Ok(())
+        } else {
+            Err(TableError::ColOutOfBounds { index, cols })
+        }
+    }
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TableError::RowOutOfBounds { index, rows } => {
+                write!(
+                    f,
+                    "index {} is outside bounds of table rows {}",
+                    index, rows
+                )
+            }
+            TableError::ColOutOfBounds { index, cols } => {
+                write!(
+                    f,
+                    "index {} is outside bounds of table columns {}",
+                    index, cols
+                )
+            }
+            TableError::UnknownColumn(name) => write!(f, "no column with header '{}'", name),
+            TableError::RowsMismatch { rows, other } => write!(
+                f,
+                "data rows {} do not match the data rows {} of the stacked table",
+                rows, other
+            ),
+        }
+    }
+}
+
+impl error::Error for TableError {}
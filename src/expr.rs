@@ -0,0 +1,1056 @@
+//! A small expression language evaluated over the entries of a row.
+//!
+//! # Syntax
+//! - Columns are referenced by header name (`price`), by a backtick quoted header name for names
+//!   which are not identifiers (`` `Total Demand` ``), or by index (`$0`).
+//! - Literals: numbers (`1`, `-2.5e3`), strings (`"Ideal"` or `'Ideal'`), `true`, `false`, `nil`.
+//! - Operators, in increasing precedence: `||`, `&&`, `== != < <= > >=`, `+ -`, `* / %`,
+//!   unary `! -`. Parentheses group.
+//! - Function calls: `is_nil(depth)`, `round(price / carat, 2)`. See [`Expr`] for the available functions.
+use super::*;
+use std::{borrow::Cow, error, fmt, str::FromStr};
+
+/// A parsed expression.
+///
+/// Expressions are parsed once and then _bound_ to a table, resolving column names against the
+/// header row.
+///
+/// ```rust
+/// # use table::*;
+/// use Entry::*;
+/// let mut r: Table<&str> = Table::new();
+/// r.add_rows(
+///     vec![
+///         vec![Obj("cut"), Obj("price"), Obj("depth")].into_iter(),
+///         vec![Obj("Ideal"), Num(326.into()), Num(61.5.into())].into_iter(),
+///         vec![Obj("Ideal"), Num(2757.into()), Num(62.8.into())].into_iter(),
+///         vec![Obj("Ideal"), Num(3000.into()), Nil].into_iter(),
+///         vec![Obj("Fair"), Num(2759.into()), Num(64.5.into())].into_iter(),
+///     ].into_iter()
+/// );
+///
+/// let expr: Expr = r#"price > 1000 && cut == "Ideal" && !is_nil(depth)"#.parse().unwrap();
+/// let p = expr.predicate(&r).unwrap();
+/// r.retain_rows(p);
+///
+/// assert_eq!(r.rows_len(), 2); // header is kept
+/// assert_eq!(r.row(1).unwrap().nth(1), Some(&Num(2757.into())));
+/// ```
+///
+/// # Functions
+/// | Function | Description |
+/// | -------- | ----------- |
+/// | `is_nil(x)`, `is_num(x)`, `is_obj(x)` | Test the variant of `x`. |
+/// | `coalesce(x, ..)` | The first argument which is not `nil`. |
+/// | `if(c, x, y)` | `x` if `c` is true, otherwise `y`. |
+/// | `abs(x)`, `sqrt(x)` | Absolute value and square root of number `x`. |
+/// | `round(x)`, `round(x, dp)` | Round number `x` to `dp` decimal places (default 0). |
+/// | `floor(x)`, `ceil(x)` | Round number `x` down or up. |
+/// | `min(x, ..)`, `max(x, ..)` | The minimum or maximum of numbers, `nil` if any are not numbers. |
+/// | `len(s)` | Number of characters in string `s`. |
+/// | `lower(s)`, `upper(s)` | Change the case of string `s`. |
+/// | `trim(s)` | Remove leading and trailing whitespace from string `s`. |
+/// | `contains(s, p)`, `starts_with(s, p)`, `ends_with(s, p)` | Test if string `s` matches `p`. |
+///
+/// # Evaluation
+/// - Arithmetic on [`Nil`] or mismatched types evaluates to [`Nil`].
+/// - Comparisons between mismatched types are `false`, except for `!=`, which is `true`.
+/// - In a boolean context, `Nil` is `false`, numbers are `true` if non-zero and strings are `true`
+///   if non-empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expr {
+    root: Node,
+}
+
+/// An error parsing or binding an [`Expr`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprError {
+    /// The expression could not be parsed, at the byte position.
+    Parse {
+        /// Byte position in the expression source.
+        pos: usize,
+        /// Description of the error.
+        msg: String,
+    },
+    /// A column name does not match any header.
+    UnknownColumn(String),
+    /// A column index is outside the columns bounds.
+    ColumnOutOfBounds(usize),
+    /// The function name is not known.
+    UnknownFunction(String),
+    /// The function was called with the wrong number of arguments.
+    Arity {
+        /// The function name.
+        name: String,
+        /// The minimum number of arguments expected.
+        min: usize,
+        /// The maximum number of arguments expected.
+        max: usize,
+        /// The number of arguments supplied.
+        found: usize,
+    },
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ExprError::Parse { pos, msg } => write!(f, "parse error at {}: {}", pos, msg),
+            ExprError::UnknownColumn(name) => write!(f, "no column with header '{}'", name),
+            ExprError::ColumnOutOfBounds(idx) => write!(f, "column index {} out of bounds", idx),
+            ExprError::UnknownFunction(name) => write!(f, "unknown function '{}'", name),
+            ExprError::Arity {
+                name,
+                min,
+                max,
+                found,
+            } => {
+                write!(f, "function '{}' expects ", name)?;
+                match (min, max) {
+                    (min, max) if min == max => write!(f, "{}", min)?,
+                    (min, &usize::MAX) => write!(f, "at least {}", min)?,
+                    (min, max) => write!(f, "{} to {}", min, max)?,
+                }
+                write!(f, " arguments, found {}", found)
+            }
+        }
+    }
+}
+
+impl error::Error for ExprError {}
+
+impl Expr {
+    /// Parse an expression.
+    ///
+    /// Parentheses, calls and operators may be nested at most 256 deep, deeper expressions are a
+    /// parse error. Each binary operator in a chain such as `a + b + c` counts as a level.
+    pub fn parse(src: &str) -> Result<Self, ExprError> {
+        let tokens = lex(src)?;
+        let mut parser = Parser {
+            tokens,
+            idx: 0,
+            end: src.len(),
+            depth: 0,
+        };
+        let root = parser.expr()?;
+        match parser.peek() {
+            None => Ok(Self { root }),
+            Some(_) => Err(parser.err("unexpected trailing input")),
+        }
+    }
+
+    /// Bind the expression to `table`, returning a predicate usable with [`Table::retain_rows`].
+    ///
+    /// Column names are resolved against the header row, which is always retained. If the table
+    /// does not have a header row, only index references (`$0`) can be used.
+    pub fn predicate<T: AsRef<str>>(
+        &self,
+        table: &Table<T>,
+    ) -> Result<impl Fn(usize, std::slice::Iter<Entry<T>>) -> bool, ExprError> {
+        let node = self.bind(table)?;
+        let header = table.header;
+        Ok(move |idx, row: std::slice::Iter<Entry<T>>| {
+            (header && idx == 0) || node.eval(row.as_slice()).truthy()
+        })
+    }
+
+    /// Resolve column references against the table's header row.
+    pub(crate) fn bind<T: AsRef<str>>(&self, table: &Table<T>) -> Result<Node, ExprError> {
+        self.root.bind(table)
+    }
+}
+
+impl FromStr for Expr {
+    type Err = ExprError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Expr::parse(s)
+    }
+}
+
+impl<T: AsRef<str>> Table<T> {
+    /// Retain _data_ rows which match the expression `expr`.
+    ///
+    /// See [`Expr`] for the expression syntax.
+    pub fn retain_rows_where(&mut self, expr: &str) -> Result<&mut Self, ExprError> {
+        let p = Expr::parse(expr)?.predicate(self)?;
+        self.retain_rows(p);
+        Ok(self)
+    }
+}
+
+impl<T> Table<T> {
+    /// Evaluate the expression `expr` for each _data_ row and add the results as a new column.
+    ///
+    /// If the table has a header row, `name` is used as the header of the new column. Expressions
+    /// which fail to evaluate, such as arithmetic on [`Nil`] or division by zero, produce [`Nil`].
+    /// Boolean results are represented as `Num(1)` and `Num(0)`. See [`Expr`] for the expression
+    /// syntax.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let mut r: DataTable = Table::new();
+    /// r.add_rows(
+    ///     vec![
+    ///         vec![Obj("cut".into()), Obj("carat".into()), Obj("price".into())].into_iter(),
+    ///         vec![Obj("Ideal".into()), Num(0.5.into()), Num(326.into())].into_iter(),
+    ///         vec![Obj("Fair".into()), Nil, Num(2757.into())].into_iter(),
+    ///     ].into_iter()
+    /// );
+    ///
+    /// r.derive_col("ppc", "round(price / carat, 2)").unwrap();
+    /// r.derive_col("CUT", "upper(cut)").unwrap();
+    ///
+    /// let ppc: Vec<_> = r.col(3).unwrap().cloned().collect();
+    /// assert_eq!(ppc, vec![Obj("ppc".into()), Num(652.into()), Nil]);
+    /// assert_eq!(r.row(2).unwrap().nth(4), Some(&Obj("FAIR".into())));
+    /// ```
+    ///
+    /// # Parallelisation
+    /// `derive_col` evaluates the expression over the rows in parallel.
+    pub fn derive_col(&mut self, name: &str, expr: &str) -> Result<&mut Self, ExprError>
+    where
+        T: AsRef<str> + for<'s> From<&'s str> + Send + Sync,
+    {
+        let node = Expr::parse(expr)?.bind(self)?;
+        let header = self.header;
+
+        let mut col = self
+            .data
+            .par_iter()
+            .enumerate()
+            .map(|(i, row)| match header && i == 0 {
+                true => Obj(T::from(name)),
+                false => node.eval(row).into_entry(),
+            })
+            .collect::<Vec<_>>();
+
+        if header && col.is_empty() {
+            col.push(Obj(T::from(name)));
+        }
+
+        Ok(self.add_col(col.into_iter()))
+    }
+}
+
+// ########### EVALUATION ################################################################
+/// The result of evaluating an expression.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Value<'a> {
+    Nil,
+    Num(Number),
+    Bool(bool),
+    Str(Cow<'a, str>),
+}
+
+impl<'a> Value<'a> {
+    fn truthy(&self) -> bool {
+        match self {
+            Value::Nil => false,
+            Value::Num(n) => n.as_f64() != 0.0,
+            Value::Bool(b) => *b,
+            Value::Str(s) => !s.is_empty(),
+        }
+    }
+
+    fn into_entry<T: for<'s> From<&'s str>>(self) -> Entry<T> {
+        match self {
+            Value::Nil => Nil,
+            Value::Num(n) => Num(n),
+            Value::Bool(b) => Num(u8::from(b).into()),
+            Value::Str(s) => Obj(T::from(s.as_ref())),
+        }
+    }
+
+    fn str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn num(&self) -> Option<Number> {
+        match self {
+            Value::Num(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    fn compare(&self, rhs: &Value) -> Option<Ordering> {
+        match (self, rhs) {
+            (Value::Nil, Value::Nil) => Some(Ordering::Equal),
+            (Value::Num(a), Value::Num(b)) => Some(a.cmp(b)),
+            (Value::Bool(a), Value::Bool(b)) => Some(a.cmp(b)),
+            (Value::Str(a), Value::Str(b)) => Some(a.cmp(b)),
+            _ => None,
+        }
+    }
+}
+
+impl<'a, T: AsRef<str>> From<&'a Entry<T>> for Value<'a> {
+    fn from(entry: &'a Entry<T>) -> Self {
+        match entry {
+            Nil => Value::Nil,
+            Bool(b) => Value::Bool(*b),
+            Num(n) => Value::Num(*n),
+            Obj(t) => Value::Str(Cow::Borrowed(t.as_ref())),
+            e => Value::Str(e.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum UnOp {
+    Not,
+    Neg,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BinOp {
+    Or,
+    And,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Func {
+    IsNil,
+    IsNum,
+    IsObj,
+    Coalesce,
+    If,
+    Abs,
+    Round,
+    Floor,
+    Ceil,
+    Sqrt,
+    Min,
+    Max,
+    Len,
+    Lower,
+    Upper,
+    Trim,
+    Contains,
+    StartsWith,
+    EndsWith,
+}
+
+impl Func {
+    /// The function and its minimum and maximum number of arguments.
+    fn lookup(name: &str) -> Option<(Func, usize, usize)> {
+        use Func::*;
+        let x = match name {
+            "is_nil" => (IsNil, 1, 1),
+            "is_num" => (IsNum, 1, 1),
+            "is_obj" => (IsObj, 1, 1),
+            "coalesce" => (Coalesce, 1, usize::MAX),
+            "if" => (If, 3, 3),
+            "abs" => (Abs, 1, 1),
+            "round" => (Round, 1, 2),
+            "floor" => (Floor, 1, 1),
+            "ceil" => (Ceil, 1, 1),
+            "sqrt" => (Sqrt, 1, 1),
+            "min" => (Min, 1, usize::MAX),
+            "max" => (Max, 1, usize::MAX),
+            "len" => (Len, 1, 1),
+            "lower" => (Lower, 1, 1),
+            "upper" => (Upper, 1, 1),
+            "trim" => (Trim, 1, 1),
+            "contains" => (Contains, 2, 2),
+            "starts_with" => (StartsWith, 2, 2),
+            "ends_with" => (EndsWith, 2, 2),
+            _ => return None,
+        };
+        Some(x)
+    }
+
+    fn call<'a>(self, args: Vec<Value<'a>>) -> Value<'a> {
+        use Func::*;
+
+        let float = |a: &Value, f: fn(f64) -> f64| {
+            a.num()
+                .and_then(|n| match num::int(n) {
+                    Some(_) => Some(n),
+                    None => num::float(f(n.as_f64())),
+                })
+                .map_or(Value::Nil, Value::Num)
+        };
+
+        let mut iter = args.iter();
+        let a = iter.next().unwrap_or(&Value::Nil);
+        let b = iter.next().unwrap_or(&Value::Nil);
+        match self {
+            IsNil => Value::Bool(matches!(a, Value::Nil)),
+            IsNum => Value::Bool(matches!(a, Value::Num(_))),
+            IsObj => Value::Bool(matches!(a, Value::Str(_))),
+            Coalesce => args
+                .into_iter()
+                .find(|x| !matches!(x, Value::Nil))
+                .unwrap_or(Value::Nil),
+            If => {
+                let x = a.truthy() as usize;
+                args.into_iter().nth(2 - x).unwrap_or(Value::Nil)
+            }
+            Abs => a
+                .num()
+                .and_then(|n| match n.as_f64() < 0.0 {
+                    true => num::neg(n),
+                    false => Some(n),
+                })
+                .map_or(Value::Nil, Value::Num),
+            Round if args.len() == 1 => float(a, f64::round),
+            Round => match (a.num(), b.num().and_then(num::int)) {
+                (Some(x), Some(dp)) if num::int(x).is_some() && dp >= 0 => Value::Num(x),
+                (Some(x), Some(dp)) => {
+                    let p = 10f64.powi(dp.clamp(-300, 300) as i32);
+                    num::float((x.as_f64() * p).round() / p).map_or(Value::Nil, Value::Num)
+                }
+                _ => Value::Nil,
+            },
+            Floor => float(a, f64::floor),
+            Ceil => float(a, f64::ceil),
+            Sqrt => a
+                .num()
+                .and_then(|n| num::float(n.as_f64().sqrt()))
+                .map_or(Value::Nil, Value::Num),
+            Min | Max => args
+                .iter()
+                .map(Value::num)
+                .try_fold(None, |acc: Option<Number>, n| {
+                    let n = n?;
+                    Some(Some(match (acc, self) {
+                        (None, _) => n,
+                        (Some(acc), Min) => acc.min(n),
+                        (Some(acc), _) => acc.max(n),
+                    }))
+                })
+                .flatten()
+                .map_or(Value::Nil, Value::Num),
+            Len => a
+                .str()
+                .map_or(Value::Nil, |s| Value::Num(s.chars().count().into())),
+            Lower => a
+                .str()
+                .map_or(Value::Nil, |s| Value::Str(s.to_lowercase().into())),
+            Upper => a
+                .str()
+                .map_or(Value::Nil, |s| Value::Str(s.to_uppercase().into())),
+            Trim => a
+                .str()
+                .map_or(Value::Nil, |s| Value::Str(s.trim().to_string().into())),
+            Contains | StartsWith | EndsWith => match (a.str(), b.str()) {
+                (Some(s), Some(p)) => Value::Bool(match self {
+                    Contains => s.contains(p),
+                    StartsWith => s.starts_with(p),
+                    _ => s.ends_with(p),
+                }),
+                _ => Value::Nil,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Node {
+    Lit(Value<'static>),
+    Name(String),
+    Col(usize),
+    Unary(UnOp, Box<Node>),
+    Binary(BinOp, Box<Node>, Box<Node>),
+    Call(Func, Vec<Node>),
+}
+
+impl Node {
+    fn bind<T: AsRef<str>>(&self, table: &Table<T>) -> Result<Node, ExprError> {
+        Ok(match self {
+            Node::Lit(v) => Node::Lit(v.clone()),
+            Node::Name(name) => table
+                .col_by_name(name)
+                .map(Node::Col)
+                .ok_or_else(|| ExprError::UnknownColumn(name.clone()))?,
+            Node::Col(idx) if *idx >= table.cols_len() => {
+                return Err(ExprError::ColumnOutOfBounds(*idx))
+            }
+            Node::Col(idx) => Node::Col(*idx),
+            Node::Unary(op, x) => Node::Unary(*op, Box::new(x.bind(table)?)),
+            Node::Binary(op, a, b) => {
+                Node::Binary(*op, Box::new(a.bind(table)?), Box::new(b.bind(table)?))
+            }
+            Node::Call(f, args) => Node::Call(
+                *f,
+                args.iter()
+                    .map(|x| x.bind(table))
+                    .collect::<Result<_, _>>()?,
+            ),
+        })
+    }
+
+    /// Evaluate a _bound_ node against a row.
+    pub(crate) fn eval<'a, T: AsRef<str>>(&self, row: &'a [Entry<T>]) -> Value<'a> {
+        match self {
+            Node::Lit(v) => v.clone(),
+            Node::Name(_) => Value::Nil,
+            Node::Col(idx) => row.get(*idx).map_or(Value::Nil, Value::from),
+            Node::Unary(UnOp::Not, x) => Value::Bool(!x.eval(row).truthy()),
+            Node::Unary(UnOp::Neg, x) => match x.eval(row) {
+                Value::Num(n) => num::neg(n).map_or(Value::Nil, Value::Num),
+                _ => Value::Nil,
+            },
+            Node::Binary(BinOp::And, a, b) => {
+                Value::Bool(a.eval(row).truthy() && b.eval(row).truthy())
+            }
+            Node::Binary(BinOp::Or, a, b) => {
+                Value::Bool(a.eval(row).truthy() || b.eval(row).truthy())
+            }
+            Node::Binary(op, a, b) => binary(*op, a.eval(row), b.eval(row)),
+            Node::Call(f, args) => f.call(args.iter().map(|x| x.eval(row)).collect()),
+        }
+    }
+}
+
+fn binary<'a>(op: BinOp, a: Value<'a>, b: Value<'a>) -> Value<'a> {
+    use BinOp::*;
+    let cmp = a.compare(&b);
+    match op {
+        Eq => Value::Bool(cmp == Some(Ordering::Equal)),
+        Ne => Value::Bool(cmp != Some(Ordering::Equal)),
+        Lt => Value::Bool(cmp == Some(Ordering::Less)),
+        Le => Value::Bool(matches!(cmp, Some(Ordering::Less | Ordering::Equal))),
+        Gt => Value::Bool(cmp == Some(Ordering::Greater)),
+        Ge => Value::Bool(matches!(cmp, Some(Ordering::Greater | Ordering::Equal))),
+        Add | Sub | Mul | Div | Rem => match (a, b) {
+            (Value::Num(x), Value::Num(y)) => {
+                let f = match op {
+                    Add => num::add,
+                    Sub => num::sub,
+                    Mul => num::mul,
+                    Div => num::div,
+                    _ => num::rem,
+                };
+                f(x, y).map_or(Value::Nil, Value::Num)
+            }
+            (Value::Str(x), Value::Str(y)) if op == Add => Value::Str(x + y),
+            _ => Value::Nil,
+        },
+        And | Or => Value::Bool(match op {
+            And => a.truthy() && b.truthy(),
+            _ => a.truthy() || b.truthy(),
+        }),
+    }
+}
+
+// ########### PARSING ###################################################################
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(Number),
+    Str(String),
+    Ident(String),
+    Col(usize),
+    Sym(&'static str),
+}
+
+const SYMBOLS: &[&str] = &[
+    "==", "!=", "<=", ">=", "&&", "||", "<", ">", "!", "+", "-", "*", "/", "%", "(", ")", ",",
+];
+
+fn lex(src: &str) -> Result<Vec<(usize, Token)>, ExprError> {
+    let err = |pos, msg: &str| ExprError::Parse {
+        pos,
+        msg: msg.to_string(),
+    };
+
+    let mut tokens = Vec::new();
+    let mut chars = src.char_indices().peekable();
+
+    while let Some(&(pos, c)) = chars.peek() {
+        let rem = &src[pos..];
+        let token = if c.is_whitespace() {
+            chars.next();
+            continue;
+        } else if c.is_ascii_digit() || c == '.' {
+            let len = rem
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+                .unwrap_or(rem.len());
+            // exponent signs
+            let len = match rem[..len].ends_with(['e', 'E']) && rem[len..].starts_with(['+', '-']) {
+                true => {
+                    len + 1
+                        + rem[len + 1..]
+                            .find(|c: char| !c.is_ascii_digit())
+                            .unwrap_or(rem.len() - len - 1)
+                }
+                false => len,
+            };
+            let n = rem[..len]
+                .parse::<Number>()
+                .map_err(|_| err(pos, "invalid number"))?;
+            (len, Token::Num(n))
+        } else if c == '"' || c == '\'' || c == '`' {
+            let mut s = String::new();
+            let mut escaped = false;
+            let mut len = None;
+            for (i, ch) in rem.char_indices().skip(1) {
+                match (escaped, ch) {
+                    (false, '\\') => escaped = true,
+                    (false, ch) if ch == c => {
+                        len = Some(i + 1);
+                        break;
+                    }
+                    (true, 'n') => {
+                        s.push('\n');
+                        escaped = false;
+                    }
+                    (true, 't') => {
+                        s.push('\t');
+                        escaped = false;
+                    }
+                    (_, ch) => {
+                        s.push(ch);
+                        escaped = false;
+                    }
+                }
+            }
+            let len = len.ok_or_else(|| err(pos, "unterminated string"))?;
+            match c {
+                '`' => (len, Token::Ident(s)),
+                _ => (len, Token::Str(s)),
+            }
+        } else if c == '$' {
+            let len = rem[1..]
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rem.len() - 1);
+            let idx = rem[1..=len]
+                .parse()
+                .map_err(|_| err(pos, "expecting column index"))?;
+            (len + 1, Token::Col(idx))
+        } else if c.is_alphabetic() || c == '_' {
+            let len = rem
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rem.len());
+            (len, Token::Ident(rem[..len].to_string()))
+        } else if let Some(&sym) = SYMBOLS.iter().find(|s| rem.starts_with(**s)) {
+            (sym.len(), Token::Sym(sym))
+        } else {
+            return Err(err(pos, &format!("unexpected character '{}'", c)));
+        };
+
+        let (len, token) = token;
+        tokens.push((pos, token));
+        while chars.peek().map(|x| x.0 < pos + len).unwrap_or_default() {
+            chars.next();
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    idx: usize,
+    end: usize,
+    /// The current nesting of parentheses, calls and operators.
+    depth: usize,
+}
+
+/// The maximum nesting of an expression, which bounds the parser's recursion.
+const MAX_DEPTH: usize = 256;
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.idx).map(|x| &x.1)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let t = self.tokens.get(self.idx).map(|x| x.1.clone());
+        self.idx += 1;
+        t
+    }
+
+    fn err(&self, msg: &str) -> ExprError {
+        ExprError::Parse {
+            pos: self.tokens.get(self.idx).map_or(self.end, |x| x.0),
+            msg: msg.to_string(),
+        }
+    }
+
+    /// Consume the symbol if it is next.
+    fn eat(&mut self, sym: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Sym(s)) if *s == sym);
+        if found {
+            self.idx += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, sym: &str) -> Result<(), ExprError> {
+        if self.eat(sym) {
+            Ok(())
+        } else {
+            Err(self.err(&format!("expecting '{}'", sym)))
+        }
+    }
+
+    fn expr(&mut self) -> Result<Node, ExprError> {
+        self.binary(0)
+    }
+
+    /// Parse a nested item with `f`, failing if the expression is nested too deeply.
+    fn nested<F>(&mut self, f: F) -> Result<Node, ExprError>
+    where
+        F: FnOnce(&mut Self) -> Result<Node, ExprError>,
+    {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.err("expression is nested too deeply"));
+        }
+        self.depth += 1;
+        let x = f(self);
+        self.depth -= 1;
+        x
+    }
+
+    /// Precedence climbing over the binary operators with at least precedence `min`, lowest
+    /// precedence first.
+    fn binary(&mut self, min: usize) -> Result<Node, ExprError> {
+        const LEVELS: &[&[(&str, BinOp)]] = &[
+            &[("||", BinOp::Or)],
+            &[("&&", BinOp::And)],
+            &[
+                ("==", BinOp::Eq),
+                ("!=", BinOp::Ne),
+                ("<=", BinOp::Le),
+                (">=", BinOp::Ge),
+                ("<", BinOp::Lt),
+                (">", BinOp::Gt),
+            ],
+            &[("+", BinOp::Add), ("-", BinOp::Sub)],
+            &[("*", BinOp::Mul), ("/", BinOp::Div), ("%", BinOp::Rem)],
+        ];
+
+        let depth = self.depth;
+        let mut lhs = self.unary()?;
+        // operators of the same level are left associative, so the rhs binds tighter
+        while let Some((level, op)) = LEVELS.iter().enumerate().skip(min).find_map(|(l, ops)| {
+            ops.iter()
+                .find(|(s, _)| self.eat(s))
+                .map(|&(_, op)| (l, op))
+        }) {
+            // each operator nests the lhs a level deeper
+            if self.depth >= MAX_DEPTH {
+                return Err(self.err("expression is nested too deeply"));
+            }
+            self.depth += 1;
+            let rhs = self.binary(level + 1)?;
+            lhs = Node::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        self.depth = depth;
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Node, ExprError> {
+        if self.eat("!") {
+            Ok(Node::Unary(UnOp::Not, Box::new(self.nested(Self::unary)?)))
+        } else if self.eat("-") {
+            Ok(match self.nested(Self::unary)? {
+                Node::Lit(Value::Num(n)) => Node::Lit(
+                    num::neg(n)
+                        .map(Value::Num)
+                        .ok_or_else(|| self.err("number overflow"))?,
+                ),
+                x => Node::Unary(UnOp::Neg, Box::new(x)),
+            })
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Node, ExprError> {
+        let start = self.idx;
+        match self.next() {
+            Some(Token::Num(n)) => Ok(Node::Lit(Value::Num(n))),
+            Some(Token::Str(s)) => Ok(Node::Lit(Value::Str(s.into()))),
+            Some(Token::Col(idx)) => Ok(Node::Col(idx)),
+            Some(Token::Sym("(")) => {
+                let x = self.nested(Self::expr)?;
+                self.expect(")")?;
+                Ok(x)
+            }
+            Some(Token::Ident(name)) if self.eat("(") => self.call_args(name),
+            Some(Token::Ident(name)) => Ok(match name.as_str() {
+                "nil" => Node::Lit(Value::Nil),
+                "true" => Node::Lit(Value::Bool(true)),
+                "false" => Node::Lit(Value::Bool(false)),
+                _ => Node::Name(name),
+            }),
+            _ => {
+                self.idx = start;
+                Err(self.err("expecting a value"))
+            }
+        }
+    }
+
+    /// The arguments of a call to `name`, after the opening parenthesis.
+    fn call_args(&mut self, name: String) -> Result<Node, ExprError> {
+        let mut args = Vec::new();
+        if !self.eat(")") {
+            loop {
+                args.push(self.nested(Self::expr)?);
+                if self.eat(")") {
+                    break;
+                }
+                self.expect(",")?;
+            }
+        }
+        let (f, min, max) =
+            Func::lookup(&name).ok_or_else(|| ExprError::UnknownFunction(name.clone()))?;
+        if args.len() < min || args.len() > max {
+            return Err(ExprError::Arity {
+                name,
+                min,
+                max,
+                found: args.len(),
+            });
+        }
+        Ok(Node::Call(f, args))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table<&'static str> {
+        Table::from(vec![
+            vec![Obj("cut"), Obj("price"), Obj("depth"), Obj("Total Demand")],
+            vec![Obj("Ideal"), Num(326.into()), Num(61.5.into()), Nil],
+            vec![Obj("Premium"), Num(2757.into()), Nil, Num(1.into())],
+            vec![Obj("Ideal"), Num(2757.into()), Num(62.8.into()), Nil],
+            vec![
+                Obj("Fair"),
+                Num(337.into()),
+                Num(64.5.into()),
+                Num(2.into()),
+            ],
+        ])
+    }
+
+    fn filtered(expr: &str) -> Vec<usize> {
+        let t = table();
+        let node = Expr::parse(expr).unwrap().bind(&t).unwrap();
+        t.rows()
+            .enumerate()
+            .skip(1)
+            .map(|(i, r)| (i, r.cloned().collect::<Vec<_>>()))
+            .filter(|(_, r)| node.eval(r).truthy())
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn eval(expr: &str) -> Value<'static> {
+        let node = Expr::parse(expr).unwrap().bind(&table()).unwrap();
+        match node.eval::<&str>(&[]) {
+            Value::Str(s) => Value::Str(Cow::Owned(s.into_owned())),
+            Value::Nil => Value::Nil,
+            Value::Num(n) => Value::Num(n),
+            Value::Bool(b) => Value::Bool(b),
+        }
+    }
+
+    #[test]
+    fn literals_and_operators() {
+        assert_eq!(eval("1 + 2 * 3"), Value::Num(7.into()));
+        assert_eq!(eval("(1 + 2) * 3"), Value::Num(9.into()));
+        assert_eq!(eval("10 - 2 - 3"), Value::Num(5.into()));
+        assert_eq!(eval("7 / 2"), Value::Num(3.5.into()));
+        assert_eq!(eval("7 % 2"), Value::Num(1.into()));
+        assert_eq!(eval("-2.5e3"), Value::Num((-2500.0).into()));
+        assert_eq!(eval("1e-1"), Value::Num(0.1.into()));
+        assert_eq!(eval("-(1 + 1)"), Value::Num((-2).into()));
+        assert_eq!(eval("1 / 0"), Value::Nil);
+        assert_eq!(
+            eval("(-170141183460469231731687303715884105727 - 1) / -1"),
+            Value::Num((-(i128::MIN as f64)).into())
+        );
+        assert_eq!(eval("1 + nil"), Value::Nil);
+        assert_eq!(eval("1 + 'a'"), Value::Nil);
+        assert_eq!(eval(r#""a" + 'b\'c'"#), Value::Str("ab'c".into()));
+        assert_eq!(
+            eval("1 < 2 && 2 <= 2 && 3 > 2 && 3 >= 3"),
+            Value::Bool(true)
+        );
+        assert_eq!(eval("1 == 2 || 1 != 1"), Value::Bool(false));
+        assert_eq!(eval("!nil"), Value::Bool(true));
+        assert_eq!(eval("1 == 'a'"), Value::Bool(false));
+        assert_eq!(eval("1 != 'a'"), Value::Bool(true));
+        assert_eq!(eval("1 < 'a'"), Value::Bool(false));
+        assert_eq!(eval("nil == nil"), Value::Bool(true));
+    }
+
+    #[test]
+    fn functions() {
+        assert_eq!(eval("is_nil(nil)"), Value::Bool(true));
+        assert_eq!(eval("is_num(1)"), Value::Bool(true));
+        assert_eq!(eval("is_obj('x')"), Value::Bool(true));
+        assert_eq!(eval("abs(-3)"), Value::Num(3.into()));
+        assert_eq!(eval("len('héllo')"), Value::Num(5.into()));
+        assert_eq!(eval("upper('a') + lower('B')"), Value::Str("Ab".into()));
+        assert_eq!(eval("contains('hello', 'ell')"), Value::Bool(true));
+        assert_eq!(eval("starts_with('hello', 'he')"), Value::Bool(true));
+        assert_eq!(eval("ends_with('hello', 'he')"), Value::Bool(false));
+        assert_eq!(eval("ends_with(1, 'he')"), Value::Nil);
+        assert_eq!(eval("coalesce(nil, nil, 3, 4)"), Value::Num(3.into()));
+        assert_eq!(eval("coalesce(nil)"), Value::Nil);
+        assert_eq!(eval("if(1 > 2, 'a', 'b')"), Value::Str("b".into()));
+        assert_eq!(eval("if(1 < 2, 'a', 'b')"), Value::Str("a".into()));
+        assert_eq!(eval("round(2.345, 2)"), Value::Num(2.35.into()));
+        assert_eq!(eval("round(2.5)"), Value::Num(3.into()));
+        assert_eq!(eval("round(1234, -2)"), Value::Num(1200.into()));
+        assert_eq!(eval("round(7, 2)"), Value::Num(7.into()));
+        assert_eq!(eval("round('a', 2)"), Value::Nil);
+        assert_eq!(eval("floor(-2.5) + ceil(2.1)"), Value::Num(0.into()));
+        assert_eq!(eval("sqrt(16)"), Value::Num(4.into()));
+        assert_eq!(eval("sqrt(-1)"), Value::Nil);
+        assert_eq!(eval("min(3, 1, 2)"), Value::Num(1.into()));
+        assert_eq!(eval("max(3, 1.5, 2)"), Value::Num(3.into()));
+        assert_eq!(eval("max(3, nil)"), Value::Nil);
+        assert_eq!(eval("trim('  a b ')"), Value::Str("a b".into()));
+    }
+
+    #[test]
+    fn derive_col() {
+        let mut t = table().map_obj(String::from);
+        t.derive_col("value", "price * depth")
+            .unwrap()
+            .derive_col("label", "lower(cut) + '-' + $1")
+            .unwrap()
+            .derive_col("nil", "is_nil(depth)")
+            .unwrap();
+
+        assert_eq!(t.cols_len(), 7);
+        let col = |i| t.col(i).unwrap().cloned().collect::<Vec<_>>();
+        assert_eq!(
+            col(4),
+            vec![
+                Obj("value".to_string()),
+                Num(20049.into()),
+                Nil,
+                Num(173139.6.into()),
+                Num(21736.5.into())
+            ]
+        );
+        assert_eq!(col(5), vec![Obj("label".to_string()), Nil, Nil, Nil, Nil]);
+        assert_eq!(
+            col(6),
+            vec![
+                Obj("nil".to_string()),
+                Num(0.into()),
+                Num(1.into()),
+                Num(0.into()),
+                Num(0.into())
+            ]
+        );
+
+        let mut t = Table::<String>::new();
+        t.derive_col("x", "1").unwrap();
+        assert_eq!(t.data, vec![vec![Obj("x".to_string())]]);
+        t.set_header(false);
+        t.derive_col("y", "1 + $0").unwrap();
+        assert_eq!(t.data, vec![vec![Obj("x".to_string()), Nil]]);
+
+        let mut t = table().map_obj(String::from);
+        assert_eq!(
+            t.derive_col("x", "carat"),
+            Err(ExprError::UnknownColumn("carat".into()))
+        );
+        assert_eq!(t.cols_len(), 4);
+    }
+
+    #[test]
+    fn columns() {
+        assert_eq!(filtered("price > 1000"), vec![2, 3]);
+        assert_eq!(
+            filtered(r#"price > 1000 && cut == "Ideal" && !is_nil(depth)"#),
+            vec![3]
+        );
+        assert_eq!(filtered("is_nil(depth) || `Total Demand` == 2"), vec![2, 4]);
+        assert_eq!(filtered("$0 == 'Ideal'"), vec![1, 3]);
+        assert_eq!(filtered("lower(cut) == 'fair'"), vec![4]);
+        assert_eq!(filtered("depth"), vec![1, 3, 4]);
+    }
+
+    #[test]
+    fn retain_rows() {
+        let mut t = table();
+        t.retain_rows_where("price < 1000").unwrap();
+        assert_eq!(t.rows_len(), 3);
+        assert_eq!(t.row(0).unwrap().next(), Some(&Obj("cut")));
+
+        let mut t = table();
+        t.set_header(false);
+        t.retain_rows_where("$1 < 1000").unwrap();
+        assert_eq!(t.rows_len(), 2);
+
+        let mut t = table();
+        t.retain_rows_where("is_nil($3)").unwrap();
+        assert_eq!(t.rows_len(), 3);
+    }
+
+    #[test]
+    fn errors() {
+        let e = |s| Expr::parse(s).unwrap_err();
+        let b = |s| Expr::parse(s).unwrap().bind(&table()).unwrap_err();
+
+        assert!(matches!(e("1 +"), ExprError::Parse { pos: 3, .. }));
+        assert!(matches!(e("(1"), ExprError::Parse { pos: 2, .. }));
+        assert!(matches!(e("1 2"), ExprError::Parse { pos: 2, .. }));
+        assert!(matches!(e("'abc"), ExprError::Parse { pos: 0, .. }));
+        assert!(matches!(e("1 # 2"), ExprError::Parse { pos: 2, .. }));
+        assert!(matches!(e("1..2"), ExprError::Parse { pos: 0, .. }));
+
+        // deep nesting is an error rather than a stack overflow
+        let deep = "(".repeat(200_000) + "1";
+        assert!(matches!(e(&deep), ExprError::Parse { pos: 257, .. }));
+        let deep = "!".repeat(200_000) + "true";
+        assert!(matches!(e(&deep), ExprError::Parse { pos: 257, .. }));
+        let deep = "-".repeat(200_000) + "1";
+        assert!(matches!(e(&deep), ExprError::Parse { .. }));
+        let deep = "1".to_string() + &" + 1".repeat(50_000);
+        assert!(matches!(e(&deep), ExprError::Parse { pos: 1028, .. }));
+        let deep = "(".repeat(200) + &"1 * 1 + ".repeat(60) + "1" + &")".repeat(200);
+        assert!(matches!(e(&deep), ExprError::Parse { .. }));
+        let ok = "(".repeat(255) + "1 + 1" + &")".repeat(255);
+        assert_eq!(eval(&ok), Value::Num(2.into()));
+        let ok = "1".to_string() + &" + 1".repeat(256);
+        assert_eq!(eval(&ok), Value::Num(257.into()));
+        let ok = "!".repeat(256) + "true";
+        assert_eq!(eval(&ok), Value::Bool(true));
+        assert_eq!(e("foo(1)"), ExprError::UnknownFunction("foo".into()));
+        assert_eq!(
+            e("is_nil(1, 2)"),
+            ExprError::Arity {
+                name: "is_nil".into(),
+                min: 1,
+                max: 1,
+                found: 2
+            }
+        );
+        assert_eq!(b("carat > 1"), ExprError::UnknownColumn("carat".into()));
+        assert_eq!(b("$4 > 1"), ExprError::ColumnOutOfBounds(4));
+
+        let mut t = table();
+        t.set_header(false);
+        assert_eq!(
+            Expr::parse("price").unwrap().bind(&t).unwrap_err(),
+            ExprError::UnknownColumn("price".into())
+        );
+    }
+}
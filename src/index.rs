@@ -0,0 +1,243 @@
+use super::*;
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    ops::{Bound, RangeBounds},
+};
+
+/// The kind of [`ColumnIndex`] to build.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum IndexKind {
+    /// A hash index, for constant time equality lookups.
+    Hash,
+    /// A sorted index, for logarithmic time equality and range lookups.
+    Sorted,
+}
+
+/// An index over the entries of a column, mapping entries to _data_ row indices.
+///
+/// The index borrows the table, so the table cannot be mutated while the index is alive and the
+/// index never goes stale. Rebuild the index after mutating the table.
+///
+/// Row indices are table row indices, and are returned in ascending order. Entries are ordered
+/// using [`Entry::total_cmp`].
+#[derive(Debug)]
+pub struct ColumnIndex<'a, T> {
+    col: usize,
+    map: Map<'a, T>,
+}
+
+#[derive(Debug)]
+enum Map<'a, T> {
+    Hash(HashMap<&'a Entry<T>, Vec<usize>>),
+    /// Entries in order with their rows, rows ascending for equal entries.
+    Sorted {
+        keys: Vec<&'a Entry<T>>,
+        rows: Vec<usize>,
+    },
+}
+
+impl<T: Hash + Ord + Sync> Table<T> {
+    /// Build an index over the _data_ entries of column `col`.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let r: Table<&str> = Table::from(vec![
+    ///     vec![Obj("id"), Obj("price")],
+    ///     vec![Obj("a"), Num(326.into())],
+    ///     vec![Obj("b"), Num(334.into())],
+    ///     vec![Obj("a"), Num(327.into())],
+    /// ]);
+    ///
+    /// let ids = r.build_index(0, IndexKind::Hash);
+    /// assert_eq!(ids.lookup(&Obj("a")), &[1, 3]);
+    /// assert!(!ids.contains(&Obj("c")));
+    ///
+    /// let prices = r.build_index(1, IndexKind::Sorted);
+    /// assert_eq!(prices.range(Num(327.into())..), vec![2, 3]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// Entries are hashed or sorted in parallel.
+    pub fn build_index(&self, col: usize, kind: IndexKind) -> ColumnIndex<'_, T> {
+        or_panic(TableError::check_col(col, self.cols_len()));
+        let s = if self.header { 1 } else { 0 };
+        let data = self.data.get(s..).unwrap_or_default();
+        let entries = data
+            .par_iter()
+            .enumerate()
+            .map(|(i, row)| (&row[col], i + s));
+
+        let map = match kind {
+            IndexKind::Hash => Map::Hash(
+                entries
+                    .fold(HashMap::new, |mut map: HashMap<_, Vec<_>>, (e, i)| {
+                        map.entry(e).or_default().push(i);
+                        map
+                    })
+                    .reduce(HashMap::new, |mut a, b| {
+                        // b follows a in row order
+                        for (e, rows) in b {
+                            a.entry(e).or_default().extend(rows);
+                        }
+                        a
+                    }),
+            ),
+            IndexKind::Sorted => {
+                let mut pairs = entries.collect::<Vec<_>>();
+                pairs.par_sort_by(|a, b| a.0.total_cmp(b.0));
+                let (keys, rows) = pairs.into_iter().unzip();
+                Map::Sorted { keys, rows }
+            }
+        };
+
+        ColumnIndex { col, map }
+    }
+}
+
+impl<'a, T: Hash + Ord> ColumnIndex<'a, T> {
+    /// The indexed column.
+    pub fn col(&self) -> usize {
+        self.col
+    }
+
+    /// The kind of index.
+    pub fn kind(&self) -> IndexKind {
+        match self.map {
+            Map::Hash(_) => IndexKind::Hash,
+            Map::Sorted { .. } => IndexKind::Sorted,
+        }
+    }
+
+    /// The rows with entries equal to `key`.
+    pub fn lookup(&self, key: &Entry<T>) -> &[usize] {
+        match &self.map {
+            Map::Hash(map) => map.get(key).map(Vec::as_slice).unwrap_or_default(),
+            Map::Sorted { keys, rows } => {
+                let lo = keys.partition_point(|k| k.total_cmp(key) == Ordering::Less);
+                let hi = keys.partition_point(|k| k.total_cmp(key) != Ordering::Greater);
+                &rows[lo..hi]
+            }
+        }
+    }
+
+    /// There is a row with an entry equal to `key`.
+    pub fn contains(&self, key: &Entry<T>) -> bool {
+        !self.lookup(key).is_empty()
+    }
+
+    /// The rows with entries within `range`.
+    ///
+    /// A hash index scans its distinct entries, a sorted index searches for the bounds.
+    pub fn range<R: RangeBounds<Entry<T>>>(&self, range: R) -> Vec<usize> {
+        let below = |k: &Entry<T>| match range.start_bound() {
+            Bound::Included(b) => k.total_cmp(b) == Ordering::Less,
+            Bound::Excluded(b) => k.total_cmp(b) != Ordering::Greater,
+            Bound::Unbounded => false,
+        };
+        let above = |k: &Entry<T>| match range.end_bound() {
+            Bound::Included(b) => k.total_cmp(b) == Ordering::Greater,
+            Bound::Excluded(b) => k.total_cmp(b) != Ordering::Less,
+            Bound::Unbounded => false,
+        };
+
+        let mut v = match &self.map {
+            Map::Hash(map) => map
+                .iter()
+                .filter(|(k, _)| !below(k) && !above(k))
+                .flat_map(|(_, rows)| rows.iter().copied())
+                .collect(),
+            Map::Sorted { keys, rows } => {
+                let lo = keys.partition_point(|k| below(k));
+                let hi = keys.partition_point(|k| !above(k)).max(lo);
+                rows[lo..hi].to_vec()
+            }
+        };
+        v.sort_unstable();
+        v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n(x: i32) -> Entry<&'static str> {
+        Num(x.into())
+    }
+
+    fn table() -> Table<&'static str> {
+        Table::from(vec![
+            vec![Obj("k")],
+            vec![n(5)],
+            vec![Obj("a")],
+            vec![n(1)],
+            vec![Nil],
+            vec![n(5)],
+            vec![n(3)],
+            vec![Obj("a")],
+        ])
+    }
+
+    #[test]
+    fn lookups() {
+        let t = table();
+        for kind in [IndexKind::Hash, IndexKind::Sorted] {
+            let idx = t.build_index(0, kind);
+            assert_eq!(idx.kind(), kind);
+            assert_eq!(idx.col(), 0);
+            assert_eq!(idx.lookup(&n(5)), &[1, 5]);
+            assert_eq!(idx.lookup(&Obj("a")), &[2, 7]);
+            assert_eq!(idx.lookup(&Nil), &[4]);
+            assert_eq!(idx.lookup(&Obj("k")), &[] as &[usize]);
+            assert!(idx.contains(&n(3)));
+            assert!(!idx.contains(&n(4)));
+        }
+
+        let mut t = table();
+        t.set_header(false);
+        let idx = t.build_index(0, IndexKind::Sorted);
+        assert_eq!(idx.lookup(&Obj("k")), &[0]);
+    }
+
+    #[test]
+    fn ranges() {
+        let t = table();
+        for kind in [IndexKind::Hash, IndexKind::Sorted] {
+            let idx = t.build_index(0, kind);
+            assert_eq!(idx.range(n(2)..n(5)), vec![6]);
+            assert_eq!(idx.range(n(2)..=n(5)), vec![1, 5, 6]);
+            assert_eq!(idx.range(..n(3)), vec![3, 4]);
+            assert_eq!(idx.range(n(3)..), vec![1, 2, 5, 6, 7]);
+            assert_eq!(idx.range(..), vec![1, 2, 3, 4, 5, 6, 7]);
+            assert_eq!(
+                idx.range((Bound::Excluded(n(3)), Bound::Excluded(Obj("a")))),
+                vec![1, 5]
+            );
+            assert_eq!(idx.range(n(5)..n(1)), Vec::<usize>::new());
+        }
+    }
+
+    #[test]
+    fn large() {
+        let mut t = Table::<()>::new();
+        t.set_header(false);
+        t.add_rows((0..10_000).map(|i| once(Num((i % 100).into()))));
+        let hash = t.build_index(0, IndexKind::Hash);
+        let sorted = t.build_index(0, IndexKind::Sorted);
+        let expected = (0..100).map(|i| i * 100 + 42).collect::<Vec<_>>();
+        assert_eq!(hash.lookup(&Num(42.into())), expected.as_slice());
+        assert_eq!(sorted.lookup(&Num(42.into())), expected.as_slice());
+        assert_eq!(hash.range(Num(98.into())..), sorted.range(Num(98.into())..));
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_panic() {
+        table().build_index(1, IndexKind::Hash);
+    }
+}
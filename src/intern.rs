@@ -0,0 +1,267 @@
+use super::*;
+use std::{collections::HashMap, hash::Hash, mem::take, ops::Index};
+
+/// An interned object, identifying a value in a [`Pool`].
+///
+/// Symbols are cheap to copy, hash, and compare, making a `Table<Sym>` suitable for grouping,
+/// joining, and equality comparisons of low cardinality object columns. Symbols order by first
+/// occurrence, _not_ by value.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Sym(u32);
+
+/// The distinct objects of an interned table, see [`Table::intern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pool<T> {
+    values: Vec<T>,
+    counts: Vec<usize>,
+}
+
+/// Memory use of object entries before and after interning, in bytes.
+///
+/// See [`Pool::memory_report`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MemoryReport {
+    /// Bytes used by the object entries as `Entry<T>`.
+    pub before: usize,
+    /// Bytes used by the object entries as `Entry<Sym>`, plus the pool.
+    pub after: usize,
+}
+
+impl<T> Table<T> {
+    /// Intern the [`Obj`] entries, replacing equal objects with a shared [`Sym`].
+    ///
+    /// Each distinct object is moved into the returned [`Pool`] once. Other entries are kept as
+    /// is, and header objects are interned like any other.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let t: Table<String> = Table::from(vec![
+    ///     vec![Obj("cut".to_string())],
+    ///     vec![Obj("Ideal".to_string())],
+    ///     vec![Obj("Premium".to_string())],
+    ///     vec![Obj("Ideal".to_string())],
+    /// ]);
+    ///
+    /// let (t, pool) = t.intern();
+    /// assert_eq!(pool.len(), 3);
+    /// assert_eq!(t.row(1).unwrap().next(), t.row(3).unwrap().next());
+    /// assert_eq!(t.row(3).unwrap().next().and_then(Entry::obj).map(|&s| &pool[s]), Some(&"Ideal".to_string()));
+    ///
+    /// let t = t.resolve(&pool);
+    /// assert_eq!(t.row(2).unwrap().next(), Some(&Obj("Premium".to_string())));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if there are more than `u32::MAX` distinct objects.
+    ///
+    /// # Parallelisation
+    /// Distinct objects are found serially, then entries are replaced in parallel by row.
+    pub fn intern(mut self) -> (Table<Sym>, Pool<T>)
+    where
+        T: Hash + Eq + Sync,
+    {
+        let mut ids: HashMap<&T, Sym> = HashMap::new();
+        let mut firsts = Vec::new();
+        let mut counts = Vec::new();
+        for (r, row) in self.data.iter().enumerate() {
+            for (c, e) in row.iter().enumerate() {
+                if let Obj(t) = e {
+                    let next = Sym(u32::try_from(firsts.len()).expect("too many distinct objects"));
+                    let sym = *ids.entry(t).or_insert(next);
+                    if sym == next {
+                        firsts.push((r, c));
+                        counts.push(0);
+                    }
+                    counts[sym.index()] += 1;
+                }
+            }
+        }
+
+        let table = self.map_ref(|e| e.as_ref().map_obj(|t| ids[t]));
+        drop(ids);
+
+        let values = firsts
+            .into_iter()
+            .filter_map(|(r, c)| match take(&mut self.data[r][c]) {
+                Obj(t) => Some(t),
+                _ => None,
+            })
+            .collect();
+
+        (table, Pool { values, counts })
+    }
+}
+
+impl Table<Sym> {
+    /// Replace each [`Sym`] with its object in `pool`, reversing [`Table::intern`].
+    ///
+    /// # Panics
+    /// Panics if a symbol is not from `pool`.
+    ///
+    /// # Parallelisation
+    /// Objects are cloned in parallel by row.
+    pub fn resolve<T>(self, pool: &Pool<T>) -> Table<T>
+    where
+        T: Clone + Send + Sync,
+    {
+        self.map_obj(|s| pool[s].clone())
+    }
+}
+
+impl Sym {
+    /// The index of the symbol in its [`Pool`].
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl<T> Pool<T> {
+    /// The number of distinct objects.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// The pool has no objects.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Retrieve the object of `sym`.
+    pub fn get(&self, sym: Sym) -> Option<&T> {
+        self.values.get(sym.index())
+    }
+
+    /// The number of entries which were interned as `sym`.
+    pub fn count(&self, sym: Sym) -> usize {
+        self.counts.get(sym.index()).copied().unwrap_or_default()
+    }
+
+    /// Find the symbol of `value`.
+    ///
+    /// This is a linear search of the pool.
+    pub fn find(&self, value: &T) -> Option<Sym>
+    where
+        T: PartialEq,
+    {
+        self.values
+            .iter()
+            .position(|x| x == value)
+            .map(|i| Sym(i as u32))
+    }
+
+    /// Iterate over the symbols and their objects, in order of first occurrence.
+    pub fn iter(&self) -> impl Iterator<Item = (Sym, &T)> {
+        self.values
+            .iter()
+            .enumerate()
+            .map(|(i, x)| (Sym(i as u32), x))
+    }
+
+    /// Estimate the memory saved by interning.
+    ///
+    /// `heap` returns the heap allocated bytes of an object, for example `|s| s.len()` for a
+    /// `String`. Only the object entries are counted.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// let t: Table<String> = Table::from(vec![vec![Entry::Obj("Ideal".to_string()); 100]]);
+    /// let (_, pool) = t.intern();
+    /// let report = pool.memory_report(|s| s.len());
+    /// assert!(report.after < report.before);
+    /// assert_eq!(report.saved(), (report.before - report.after) as isize);
+    /// ```
+    pub fn memory_report<F>(&self, heap: F) -> MemoryReport
+    where
+        F: Fn(&T) -> usize,
+    {
+        use std::mem::size_of;
+
+        let mut report = MemoryReport {
+            before: 0,
+            after: 0,
+        };
+        for (x, &n) in self.values.iter().zip(&self.counts) {
+            let heap = heap(x);
+            report.before += n * (size_of::<Entry<T>>() + heap);
+            report.after += n * size_of::<Entry<Sym>>() + size_of::<T>() + heap;
+        }
+        report.after += size_of::<usize>() * self.counts.len();
+        report
+    }
+}
+
+impl<T> Index<Sym> for Pool<T> {
+    type Output = T;
+
+    fn index(&self, sym: Sym) -> &T {
+        &self.values[sym.index()]
+    }
+}
+
+impl MemoryReport {
+    /// Bytes saved by interning, negative if interning uses more memory.
+    pub fn saved(&self) -> isize {
+        self.before as isize - self.after as isize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table<String> {
+        let o = |s: &str| Obj(s.to_string());
+        Table::from(vec![
+            vec![o("cut"), o("color"), o("price")],
+            vec![o("Ideal"), o("E"), Num(326.into())],
+            vec![o("Premium"), o("E"), Num(326.into())],
+            vec![o("Ideal"), Nil, Num(327.into())],
+            vec![o("Good"), o("I"), Nil],
+        ])
+    }
+
+    #[test]
+    fn interning() {
+        let (t, pool) = table().intern();
+        assert!(t.header);
+        assert_eq!(pool.len(), 8);
+        assert_eq!(
+            pool.iter().map(|x| x.1.as_str()).collect::<Vec<_>>(),
+            vec!["cut", "color", "price", "Ideal", "E", "Premium", "Good", "I"]
+        );
+
+        let ideal = pool.find(&"Ideal".to_string()).unwrap();
+        assert_eq!(pool.count(ideal), 2);
+        assert_eq!(pool.count(pool.find(&"E".to_string()).unwrap()), 2);
+        assert_eq!(pool.get(ideal).map(String::as_str), Some("Ideal"));
+        assert_eq!(pool.find(&"Fair".to_string()), None);
+
+        assert_eq!(t.row(1).unwrap().next(), Some(&Obj(ideal)));
+        assert_eq!(t.row(3).unwrap().next(), Some(&Obj(ideal)));
+        assert_eq!(t.row(3).unwrap().nth(1), Some(&Nil));
+        assert_eq!(t.row(3).unwrap().nth(2), Some(&Num(327.into())));
+
+        // operations on symbols
+        let mut d = t.clone();
+        d.dedup_by(&[0], Keep::First);
+        assert_eq!(d.rows_len(), 4);
+
+        assert_eq!(t.resolve(&pool), table());
+    }
+
+    #[test]
+    fn memory() {
+        let (_, pool) = table().intern();
+        let report = pool.memory_report(|s| s.len());
+        assert!(report.before > 0);
+        assert_eq!(
+            report.saved(),
+            report.before as isize - report.after as isize
+        );
+
+        let (_, pool) = Table::<String>::new().intern();
+        assert!(pool.is_empty());
+        assert_eq!(pool.memory_report(|s| s.len()).saved(), 0);
+    }
+}
@@ -0,0 +1,722 @@
+use super::*;
+use std::ops::Deref;
+
+/// A [`Table`] which records its mutations, supporting undo and redo.
+///
+/// Each mutation is recorded as an invertible operation holding only the entries it touches, so
+/// undoing an edit does not require a copy of the table. Operations are grouped: each mutating
+/// method call is a group, and [`JournaledTable::transaction`] groups several calls into one.
+/// Undo and redo act on whole groups. Any new mutation clears the redo history.
+///
+/// The table is read through [`Deref`], and can only be mutated through the journal.
+///
+/// ```rust
+/// # use table::*;
+/// use Entry::*;
+/// let mut j: JournaledTable<&str> = JournaledTable::new(Table::from(vec![
+///     vec![Obj("item"), Obj("qty")],
+///     vec![Obj("bolt"), Num(3.into())],
+///     vec![Obj("nut"), Num(1.into())],
+/// ]));
+///
+/// j.set(1, 1, Num(4.into()));
+/// j.transaction(|j| {
+///     j.remove_row(2);
+///     j.add_row(vec![Obj("washer"), Num(9.into())].into_iter());
+/// });
+/// assert_eq!(j[(2, 0)], Obj("washer"));
+///
+/// j.undo(); // the whole transaction
+/// assert_eq!(j[(2, 0)], Obj("nut"));
+/// j.undo();
+/// assert_eq!(j[(1, 1)], Num(3.into()));
+/// j.redo();
+/// assert_eq!(j[(1, 1)], Num(4.into()));
+/// ```
+#[derive(Debug)]
+pub struct JournaledTable<T> {
+    table: Table<T>,
+    done: Vec<Vec<Op<T>>>,
+    undone: Vec<Vec<Op<T>>>,
+    /// The group being recorded by an open transaction.
+    open: Option<Vec<Op<T>>>,
+}
+
+/// A recorded mutation of a [`JournaledTable`], see [`JournaledTable::log`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Op<T>(Kind<T>);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Kind<T> {
+    /// Insert a row, `cols` is the columns length beforehand.
+    InsertRow {
+        index: usize,
+        entries: Vec<Entry<T>>,
+        cols: usize,
+    },
+    RemoveRow {
+        index: usize,
+        entries: Vec<Entry<T>>,
+    },
+    /// Insert a column, `rows` is the rows length beforehand.
+    InsertCol {
+        index: usize,
+        entries: Vec<Entry<T>>,
+        rows: usize,
+    },
+    RemoveCol {
+        index: usize,
+        entries: Vec<Entry<T>>,
+    },
+    Set {
+        row: usize,
+        col: usize,
+        old: Entry<T>,
+        new: Entry<T>,
+    },
+    /// Row `rows[i]` becomes row `i`, trailing rows are untouched.
+    Permute { rows: Vec<usize> },
+}
+
+impl<T: Clone> Op<T> {
+    fn apply(&self, table: &mut Table<T>) -> Result<(), TableError> {
+        match &self.0 {
+            Kind::InsertRow { index, entries, .. } => {
+                table.try_insert_row(*index, entries.iter().cloned())?;
+            }
+            Kind::RemoveRow { index, .. } => {
+                table.try_remove_row(*index)?;
+            }
+            Kind::InsertCol { index, entries, .. } => {
+                table.try_insert_col(*index, entries.iter().cloned())?;
+            }
+            Kind::RemoveCol { index, .. } => {
+                table.try_remove_col(*index)?;
+            }
+            Kind::Set { row, col, new, .. } => {
+                TableError::check_row(*row, table.rows_len())?;
+                TableError::check_col(*col, table.cols_len())?;
+                table.data[*row][*col] = new.clone();
+            }
+            Kind::Permute { rows } => {
+                if let Some(last) = rows.len().checked_sub(1) {
+                    TableError::check_row(last, table.rows_len())?;
+                }
+                permute(table, rows);
+            }
+        }
+        Ok(())
+    }
+
+    /// Revert the operation, which must have been the last applied to `table`.
+    fn revert(&self, table: &mut Table<T>) {
+        match &self.0 {
+            Kind::InsertRow { index, cols, .. } => {
+                table.remove_row(*index);
+                if table.cols != *cols {
+                    table.cols = *cols;
+                    table.resize_cols();
+                }
+            }
+            Kind::RemoveRow { index, entries } => {
+                table.insert_row(*index, entries.iter().cloned());
+            }
+            Kind::InsertCol { index, rows, .. } => {
+                table.remove_col(*index);
+                table.data.truncate(*rows);
+            }
+            Kind::RemoveCol { index, entries } => {
+                table.insert_col(*index, entries.iter().cloned());
+            }
+            Kind::Set { row, col, old, .. } => table.data[*row][*col] = old.clone(),
+            Kind::Permute { rows } => {
+                let mut inv = vec![0; rows.len()];
+                for (i, &r) in rows.iter().enumerate() {
+                    inv[r] = i;
+                }
+                permute(table, &inv);
+            }
+        }
+    }
+}
+
+/// Reorder the leading rows of `table`, row `rows[i]` becoming row `i`.
+fn permute<T>(table: &mut Table<T>, rows: &[usize]) {
+    let mut old = std::mem::take(&mut table.data)
+        .into_iter()
+        .map(Some)
+        .collect::<Vec<_>>();
+    let mut data = rows
+        .iter()
+        .map(|&i| old[i].take().expect("rows is a permutation"))
+        .collect::<Vec<_>>();
+    data.extend(old.into_iter().skip(rows.len()).flatten());
+    table.data = data;
+}
+
+impl<T: Clone> JournaledTable<T> {
+    /// Start journaling mutations of `table`, with an empty history.
+    pub fn new(table: Table<T>) -> Self {
+        Self {
+            table,
+            done: Vec::new(),
+            undone: Vec::new(),
+            open: None,
+        }
+    }
+
+    /// Stop journaling, returning the table.
+    pub fn into_inner(self) -> Table<T> {
+        self.table
+    }
+
+    /// Apply `op` and record it, clearing the redo history.
+    fn exec(&mut self, op: Kind<T>) {
+        let op = Op(op);
+        or_panic(op.apply(&mut self.table));
+        self.undone.clear();
+        match &mut self.open {
+            Some(group) => group.push(op),
+            None => self.done.push(vec![op]),
+        }
+    }
+
+    /// Record the mutations of `f` as a single group, undone and redone together.
+    ///
+    /// Nested transactions are part of the outermost transaction. Undo and redo do nothing within
+    /// a transaction.
+    pub fn transaction<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut Self) -> R,
+    {
+        if self.open.is_some() {
+            return f(self);
+        }
+
+        self.open = Some(Vec::new());
+        let r = f(self);
+        match self.open.take() {
+            Some(group) if !group.is_empty() => self.done.push(group),
+            _ => (),
+        }
+        r
+    }
+
+    /// Undo the last group of mutations, returning whether there was one to undo.
+    pub fn undo(&mut self) -> bool {
+        if self.open.is_some() {
+            return false;
+        }
+        match self.done.pop() {
+            Some(group) => {
+                group.iter().rev().for_each(|op| op.revert(&mut self.table));
+                self.undone.push(group);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Redo the last undone group of mutations, returning whether there was one to redo.
+    pub fn redo(&mut self) -> bool {
+        if self.open.is_some() {
+            return false;
+        }
+        match self.undone.pop() {
+            Some(group) => {
+                group
+                    .iter()
+                    .for_each(|op| or_panic(op.apply(&mut self.table)));
+                self.done.push(group);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// There is a group of mutations to undo.
+    pub fn can_undo(&self) -> bool {
+        self.open.is_none() && !self.done.is_empty()
+    }
+
+    /// There is a group of mutations to redo.
+    pub fn can_redo(&self) -> bool {
+        self.open.is_none() && !self.undone.is_empty()
+    }
+
+    /// Forget the undo and redo history.
+    pub fn clear_history(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+
+    /// The applied mutations, in order. Undone mutations are not included.
+    ///
+    /// The log can be replayed onto another table with [`Table::replay`].
+    pub fn log(&self) -> impl Iterator<Item = &Op<T>> {
+        self.done.iter().chain(self.open.as_ref()).flatten()
+    }
+
+    /// Add a row of entries, see [`Table::add_row`].
+    pub fn add_row<I, E>(&mut self, entries: I) -> &mut Self
+    where
+        I: Iterator<Item = E>,
+        E: Into<Entry<T>>,
+    {
+        let index = self.table.rows_len();
+        self.insert_row(index, entries)
+    }
+
+    /// Insert a row of entries, see [`Table::insert_row`].
+    ///
+    /// # Panics
+    /// Panics if `index` is outside the rows bounds. See [`JournaledTable::try_insert_row`].
+    pub fn insert_row<I, E>(&mut self, index: usize, entries: I) -> &mut Self
+    where
+        I: Iterator<Item = E>,
+        E: Into<Entry<T>>,
+    {
+        or_panic(self.try_insert_row(index, entries))
+    }
+
+    /// Insert a row of entries, see [`Table::try_insert_row`].
+    ///
+    /// Returns an error if `index` is outside the rows bounds.
+    pub fn try_insert_row<I, E>(
+        &mut self,
+        index: usize,
+        entries: I,
+    ) -> Result<&mut Self, TableError>
+    where
+        I: Iterator<Item = E>,
+        E: Into<Entry<T>>,
+    {
+        // inserting at the end is allowed
+        TableError::check_row(index, self.table.rows_len() + 1).map_err(|_| {
+            TableError::RowOutOfBounds {
+                index,
+                rows: self.table.rows_len(),
+            }
+        })?;
+        self.exec(Kind::InsertRow {
+            index,
+            entries: entries.map(Into::into).collect(),
+            cols: self.table.cols,
+        });
+        Ok(self)
+    }
+
+    /// Add a column of entries, see [`Table::add_col`].
+    pub fn add_col<I, E>(&mut self, entries: I) -> &mut Self
+    where
+        I: Iterator<Item = E>,
+        E: Into<Entry<T>>,
+    {
+        let index = self.table.cols_len();
+        self.insert_col(index, entries)
+    }
+
+    /// Insert a column of entries, see [`Table::insert_col`].
+    ///
+    /// # Panics
+    /// Panics if `index` is outside the columns bounds. See [`JournaledTable::try_insert_col`].
+    pub fn insert_col<I, E>(&mut self, index: usize, entries: I) -> &mut Self
+    where
+        I: Iterator<Item = E>,
+        E: Into<Entry<T>>,
+    {
+        or_panic(self.try_insert_col(index, entries))
+    }
+
+    /// Insert a column of entries, see [`Table::try_insert_col`].
+    ///
+    /// Returns an error if `index` is outside the columns bounds.
+    pub fn try_insert_col<I, E>(
+        &mut self,
+        index: usize,
+        entries: I,
+    ) -> Result<&mut Self, TableError>
+    where
+        I: Iterator<Item = E>,
+        E: Into<Entry<T>>,
+    {
+        // inserting at the end is allowed
+        TableError::check_col(index, self.table.cols_len() + 1).map_err(|_| {
+            TableError::ColOutOfBounds {
+                index,
+                cols: self.table.cols_len(),
+            }
+        })?;
+        self.exec(Kind::InsertCol {
+            index,
+            entries: entries.map(Into::into).collect(),
+            rows: self.table.rows_len(),
+        });
+        Ok(self)
+    }
+
+    /// Remove a row, see [`Table::remove_row`].
+    ///
+    /// # Panics
+    /// Panics if `index` is outside the rows bounds. See [`JournaledTable::try_remove_row`].
+    pub fn remove_row(&mut self, index: usize) -> &mut Self {
+        or_panic(self.try_remove_row(index))
+    }
+
+    /// Remove a row, see [`Table::try_remove_row`].
+    ///
+    /// Returns an error if `index` is outside the rows bounds.
+    pub fn try_remove_row(&mut self, index: usize) -> Result<&mut Self, TableError> {
+        TableError::check_row(index, self.table.rows_len())?;
+        self.exec(Kind::RemoveRow {
+            index,
+            entries: self.table.data[index].clone(),
+        });
+        Ok(self)
+    }
+
+    /// Remove a column, see [`Table::remove_col`].
+    ///
+    /// # Panics
+    /// Panics if `index` is outside the columns bounds. See [`JournaledTable::try_remove_col`].
+    pub fn remove_col(&mut self, index: usize) -> &mut Self {
+        or_panic(self.try_remove_col(index))
+    }
+
+    /// Remove a column, see [`Table::try_remove_col`].
+    ///
+    /// Returns an error if `index` is outside the columns bounds.
+    pub fn try_remove_col(&mut self, index: usize) -> Result<&mut Self, TableError> {
+        TableError::check_col(index, self.table.cols_len())?;
+        self.exec(Kind::RemoveCol {
+            index,
+            entries: self.table.data.iter().map(|r| r[index].clone()).collect(),
+        });
+        Ok(self)
+    }
+
+    /// Set the entry at `row` and `col`.
+    ///
+    /// # Panics
+    /// Panics if `row` or `col` is outside the table bounds. See [`JournaledTable::try_set`].
+    pub fn set<E: Into<Entry<T>>>(&mut self, row: usize, col: usize, entry: E) -> &mut Self {
+        or_panic(self.try_set(row, col, entry))
+    }
+
+    /// Set the entry at `row` and `col`. See [`JournaledTable::set`].
+    ///
+    /// Returns an error if `row` or `col` is outside the table bounds.
+    pub fn try_set<E: Into<Entry<T>>>(
+        &mut self,
+        row: usize,
+        col: usize,
+        entry: E,
+    ) -> Result<&mut Self, TableError> {
+        TableError::check_row(row, self.table.rows_len())?;
+        TableError::check_col(col, self.table.cols_len())?;
+        self.exec(Kind::Set {
+            row,
+            col,
+            old: self.table.data[row][col].clone(),
+            new: entry.into(),
+        });
+        Ok(self)
+    }
+
+    /// Mutate the entries of row `index` using `f`, recording the changed entries.
+    ///
+    /// The journaled counterpart of [`Table::row_mut`]. Only entries which differ after `f` are
+    /// recorded, as a single group.
+    ///
+    /// # Panics
+    /// Panics if `index` is outside the rows bounds. See [`JournaledTable::try_edit_row`].
+    pub fn edit_row<F>(&mut self, index: usize, f: F) -> &mut Self
+    where
+        T: PartialEq,
+        F: FnOnce(&mut [Entry<T>]),
+    {
+        or_panic(self.try_edit_row(index, f))
+    }
+
+    /// Mutate the entries of row `index` using `f`. See [`JournaledTable::edit_row`].
+    ///
+    /// Returns an error if `index` is outside the rows bounds.
+    pub fn try_edit_row<F>(&mut self, index: usize, f: F) -> Result<&mut Self, TableError>
+    where
+        T: PartialEq,
+        F: FnOnce(&mut [Entry<T>]),
+    {
+        TableError::check_row(index, self.table.rows_len())?;
+        let mut row = self.table.data[index].clone();
+        f(&mut row);
+        self.transaction(|j| {
+            for (col, new) in row.into_iter().enumerate() {
+                let old = &j.table.data[index][col];
+                if old != &new {
+                    let old = old.clone();
+                    j.exec(Kind::Set {
+                        row: index,
+                        col,
+                        old,
+                        new,
+                    });
+                }
+            }
+        });
+        Ok(self)
+    }
+
+    /// Sort _data_ rows by comparing entries in a column, see [`Table::sort`].
+    ///
+    /// Only the row permutation is recorded.
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds. See [`JournaledTable::try_sort`].
+    ///
+    /// # Parallelisation
+    /// The row permutation is sorted in parallel.
+    pub fn sort<F>(&mut self, col: usize, ordering: F) -> &mut Self
+    where
+        T: Sync,
+        F: Fn(&Entry<T>, &Entry<T>) -> Ordering + Sync,
+    {
+        or_panic(self.try_sort(col, ordering))
+    }
+
+    /// Sort _data_ rows by comparing entries in a column. See [`JournaledTable::sort`].
+    ///
+    /// Returns an error if `col` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// The row permutation is sorted in parallel.
+    pub fn try_sort<F>(&mut self, col: usize, ordering: F) -> Result<&mut Self, TableError>
+    where
+        T: Sync,
+        F: Fn(&Entry<T>, &Entry<T>) -> Ordering + Sync,
+    {
+        TableError::check_col(col, self.table.cols_len())?;
+        let s = if self.table.header { 1 } else { 0 };
+        let data = &self.table.data;
+        let mut rows = (0..data.len()).collect::<Vec<_>>();
+        if let Some(rows) = rows.get_mut(s..) {
+            rows.par_sort_by(|&a, &b| ordering(&data[a][col], &data[b][col]));
+        }
+        if rows.iter().enumerate().any(|(i, &r)| i != r) {
+            self.exec(Kind::Permute { rows });
+        }
+        Ok(self)
+    }
+}
+
+impl<T> Deref for JournaledTable<T> {
+    type Target = Table<T>;
+
+    fn deref(&self) -> &Table<T> {
+        &self.table
+    }
+}
+
+impl<T: Clone + Send + Sync> Clone for JournaledTable<T> {
+    fn clone(&self) -> Self {
+        Self {
+            table: self.table.clone(),
+            done: self.done.clone(),
+            undone: self.undone.clone(),
+            open: self.open.clone(),
+        }
+    }
+}
+
+impl<T: Clone> From<Table<T>> for JournaledTable<T> {
+    fn from(table: Table<T>) -> Self {
+        Self::new(table)
+    }
+}
+
+impl<T: Clone> Table<T> {
+    /// Apply a log of [`JournaledTable`] mutations, in order.
+    ///
+    /// Operations are positional, so replaying a log onto a table with the same shape as the
+    /// journaled table repeats its edits.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let base: Table<&str> = Table::from(vec![vec![Obj("a")], vec![Num(1.into())]]);
+    /// let mut j = JournaledTable::new(base.clone());
+    /// j.add_col(vec![Obj("b"), Num(2.into())].into_iter());
+    /// j.set(1, 0, Num(3.into()));
+    ///
+    /// let mut replica = base;
+    /// replica.replay(j.log());
+    /// assert_eq!(&replica, &*j);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if an operation is outside the table bounds. See [`Table::try_replay`].
+    pub fn replay<'a, I>(&mut self, ops: I) -> &mut Self
+    where
+        T: 'a,
+        I: IntoIterator<Item = &'a Op<T>>,
+    {
+        or_panic(self.try_replay(ops))
+    }
+
+    /// Apply a log of [`JournaledTable`] mutations, in order. See [`Table::replay`].
+    ///
+    /// Returns an error if an operation is outside the table bounds, in which case the operations
+    /// before it remain applied.
+    pub fn try_replay<'a, I>(&mut self, ops: I) -> Result<&mut Self, TableError>
+    where
+        T: 'a,
+        I: IntoIterator<Item = &'a Op<T>>,
+    {
+        for op in ops {
+            op.apply(self)?;
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n(x: i32) -> Entry<&'static str> {
+        Num(x.into())
+    }
+
+    fn table() -> Table<&'static str> {
+        Table::from(vec![
+            vec![Obj("a"), Obj("b")],
+            vec![n(3), n(1)],
+            vec![n(1), n(2)],
+            vec![n(2), n(3)],
+        ])
+    }
+
+    /// Undo everything checking each step returns to the previous state, then redo everything.
+    fn roundtrip(mut j: JournaledTable<&'static str>, states: Vec<Table<&'static str>>) {
+        for s in states.iter().rev().skip(1) {
+            assert!(j.undo());
+            assert_eq!(&*j, s);
+        }
+        assert!(!j.undo());
+        for s in states.iter().skip(1) {
+            assert!(j.redo());
+            assert_eq!(&*j, s);
+        }
+        assert!(!j.redo());
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut j = JournaledTable::new(table());
+        let mut states = vec![table()];
+        macro_rules! step {
+            ($e:expr) => {{
+                $e;
+                states.push(j.table.clone());
+            }};
+        }
+
+        step!(j.add_row(vec![n(4)].into_iter()));
+        step!(j.insert_row(1, vec![n(5), n(6), n(7)].into_iter()));
+        step!(j.insert_col(1, vec![Obj("c"), n(8)].into_iter()));
+        step!(j.add_col((0..8).map(n)));
+        step!(j.remove_row(2));
+        step!(j.remove_col(0));
+        step!(j.set(1, 1, Obj("x")));
+        step!(j.edit_row(3, |r| r[0] = n(0)));
+        step!(j.sort(3, |a, b| b.total_cmp(a)));
+        step!(j.transaction(|j| {
+            for _ in 0..j.rows_len() {
+                j.remove_row(0);
+            }
+        }));
+        assert_eq!(j.rows_len(), 0);
+        step!(j.add_col(vec![n(1), n(2)].into_iter()));
+
+        roundtrip(j, states);
+    }
+
+    #[test]
+    fn remove_last_col() {
+        let mut j = JournaledTable::new(Table::from(vec![vec![n(1)], vec![n(2)]]));
+        j.remove_col(0);
+        assert!(j.is_empty());
+        j.undo();
+        assert_eq!(
+            j.clone().into_inner().into_raw(),
+            vec![vec![n(1)], vec![n(2)]]
+        );
+    }
+
+    #[test]
+    fn transactions() {
+        let mut j = JournaledTable::new(table());
+        let r = j.transaction(|j| {
+            j.set(1, 0, n(9));
+            j.transaction(|j| j.remove_row(2).remove_col(1).can_undo());
+            assert!(!j.undo());
+            j.try_set(9, 0, Nil).is_err()
+        });
+        assert!(r);
+        assert_eq!(j.log().count(), 3);
+        assert!(j.undo());
+        assert_eq!(&*j, &table());
+        assert!(!j.can_undo());
+
+        // empty transactions and no-op edits are not recorded
+        j.transaction(|_| ());
+        j.edit_row(1, |_| ());
+        j.sort(0, |_, _| Ordering::Equal);
+        assert!(!j.can_undo());
+        assert!(j.can_redo());
+
+        // new mutations clear the redo history
+        j.set(1, 0, n(0));
+        assert!(!j.can_redo());
+        j.clear_history();
+        assert!(!j.can_undo());
+    }
+
+    #[test]
+    fn replays() {
+        let mut j = JournaledTable::new(table());
+        j.sort(0, Entry::total_cmp);
+        j.add_row(vec![n(4), n(4)].into_iter());
+        j.edit_row(1, |r| r[1] = Nil);
+        j.add_col(once(Obj("c")));
+        j.remove_row(2);
+        j.undo();
+
+        let mut t = table();
+        t.replay(j.log());
+        assert_eq!(&t, &*j);
+
+        let mut small = Table::from(vec![vec![n(1)]]);
+        assert_eq!(
+            small.try_replay(j.log()).err(),
+            Some(TableError::RowOutOfBounds { index: 3, rows: 1 })
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let mut j = JournaledTable::new(table());
+        assert_eq!(
+            j.try_insert_row(5, once(Nil)).err(),
+            Some(TableError::RowOutOfBounds { index: 5, rows: 4 })
+        );
+        assert_eq!(
+            j.try_insert_col(3, once(Nil)).err(),
+            Some(TableError::ColOutOfBounds { index: 3, cols: 2 })
+        );
+        assert!(j.try_remove_row(4).is_err());
+        assert!(j.try_remove_col(2).is_err());
+        assert!(j.try_edit_row(4, |_| ()).is_err());
+        assert!(j.try_sort(2, Entry::total_cmp).is_err());
+        assert!(!j.can_undo());
+    }
+}
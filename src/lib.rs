@@ -1,18 +1,57 @@
 //! Generic data table abstraction.
 #![warn(missing_docs)]
 
+mod cell;
+mod collate;
+mod columnar;
+mod concat;
+mod dedup;
+mod diff;
 mod entry;
+mod error;
+mod expr;
+mod index;
+mod intern;
+mod journal;
+mod missing;
+mod num;
+mod ops;
+mod parallel;
 mod parsing;
+mod sample;
+mod schema;
+mod sort;
+mod stats;
 #[cfg(test)]
 mod tests;
+pub mod time;
+mod topk;
+mod vector;
+mod view;
+mod window;
 
 pub use ::kserd::Number;
-pub use entry::Entry;
+pub use entry::{Entry, VariantOrder};
+pub use error::TableError;
+pub use expr::{Expr, ExprError};
 use rayon::prelude::*;
 use std::{cmp::*, iter::*};
 use Entry::*;
 
+pub use crate::cell::ColumnRef;
+pub use crate::columnar::{ColumnTable, NullBitmap};
+pub use crate::concat::{Align, ColumnSet};
+pub use crate::dedup::Keep;
+pub use crate::diff::{CellChange, RowChange, TableDiff};
+pub use crate::index::{ColumnIndex, IndexKind};
+pub use crate::intern::{MemoryReport, Pool, Sym};
+pub use crate::journal::{JournaledTable, Op};
 pub use crate::parsing::parse_dsv;
+pub use crate::schema::{EntryKind, Field, Schema, Violation};
+pub use crate::sort::{Direction, Nils};
+pub use crate::vector::Norm;
+pub use crate::view::{TableView, ViewRow};
+pub use crate::window::{Window, WindowFn};
 
 /// The main data table type.
 ///
@@ -108,14 +147,32 @@ impl<T> Table<T> {
     /// Insert a row of entries at the specified row index, shifting trailing rows down.
     ///
     /// # Panics
-    /// Panics if `index` is outside the rows bounds.
+    /// Panics if `index` is outside the rows bounds. See [`Table::try_insert_row`].
     pub fn insert_row<I, E>(&mut self, index: usize, entries: I) -> &mut Self
+    where
+        I: Iterator<Item = E>,
+        E: Into<Entry<T>>,
+    {
+        or_panic(self.try_insert_row(index, entries))
+    }
+
+    /// Insert a row of entries at the specified row index, shifting trailing rows down.
+    ///
+    /// Returns an error if `index` is outside the rows bounds.
+    pub fn try_insert_row<I, E>(
+        &mut self,
+        index: usize,
+        entries: I,
+    ) -> Result<&mut Self, TableError>
     where
         I: Iterator<Item = E>,
         E: Into<Entry<T>>,
     {
         if index > self.rows_len() {
-            panic_rows(index, self.rows_len());
+            return Err(TableError::RowOutOfBounds {
+                index,
+                rows: self.rows_len(),
+            });
         }
 
         let mut row: Vec<_> = entries.map(Into::into).collect();
@@ -133,7 +190,7 @@ impl<T> Table<T> {
             self.resize_cols();
         }
 
-        self
+        Ok(self)
     }
 
     /// Add a column of entries into the table.
@@ -209,14 +266,32 @@ impl<T> Table<T> {
     /// Insert a column of entries at the specified column index, shifting trailing columns right.
     ///
     /// # Panics
-    /// Panics if `index` is outside the columns bounds.
-    pub fn insert_col<I, E>(&mut self, index: usize, mut entries: I) -> &mut Self
+    /// Panics if `index` is outside the columns bounds. See [`Table::try_insert_col`].
+    pub fn insert_col<I, E>(&mut self, index: usize, entries: I) -> &mut Self
+    where
+        I: Iterator<Item = E>,
+        E: Into<Entry<T>>,
+    {
+        or_panic(self.try_insert_col(index, entries))
+    }
+
+    /// Insert a column of entries at the specified column index, shifting trailing columns right.
+    ///
+    /// Returns an error if `index` is outside the columns bounds.
+    pub fn try_insert_col<I, E>(
+        &mut self,
+        index: usize,
+        mut entries: I,
+    ) -> Result<&mut Self, TableError>
     where
         I: Iterator<Item = E>,
         E: Into<Entry<T>>,
     {
         if index > self.cols_len() {
-            panic_cols(index, self.cols_len());
+            return Err(TableError::ColOutOfBounds {
+                index,
+                cols: self.cols_len(),
+            });
         }
 
         for row in &mut self.data {
@@ -240,44 +315,66 @@ impl<T> Table<T> {
             self.data.push(v);
         }
 
-        self
+        Ok(self)
     }
 
     /// Remove a row of entries at the specified row index, shifting trailing rows up.
     ///
     /// # Panics
-    /// Panics if `index` is outside the rows bounds.
+    /// Panics if `index` is outside the rows bounds. See [`Table::try_remove_row`].
     pub fn remove_row(&mut self, index: usize) -> &mut Self {
-        if index >= self.rows_len() {
-            panic_rows(index, self.rows_len());
-        }
+        or_panic(self.try_remove_row(index))
+    }
+
+    /// Remove a row of entries at the specified row index, shifting trailing rows up.
+    ///
+    /// Returns an error if `index` is outside the rows bounds.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// let mut r: Table<()> = Table::new();
+    /// r.add_row(vec![Entry::Nil].into_iter());
+    ///
+    /// assert_eq!(
+    ///     r.try_remove_row(1).err(),
+    ///     Some(TableError::RowOutOfBounds { index: 1, rows: 1 })
+    /// );
+    /// assert!(r.try_remove_row(0).is_ok());
+    /// ```
+    pub fn try_remove_row(&mut self, index: usize) -> Result<&mut Self, TableError> {
+        TableError::check_row(index, self.rows_len())?;
         self.data.remove(index);
         if self.is_empty() {
             self.cols = 0;
         }
-        self
+        Ok(self)
     }
 
     /// Remove a column of entries at the specified column index, shifting trailing columns left.
     ///
     /// # Panics
-    /// Panics if `index` is outside the columns bounds.
+    /// Panics if `index` is outside the columns bounds. See [`Table::try_remove_col`].
     pub fn remove_col(&mut self, index: usize) -> &mut Self {
-        if index >= self.cols_len() {
-            panic_cols(index, self.cols_len());
-        }
+        or_panic(self.try_remove_col(index))
+    }
+
+    /// Remove a column of entries at the specified column index, shifting trailing columns left.
+    ///
+    /// Returns an error if `index` is outside the columns bounds.
+    pub fn try_remove_col(&mut self, index: usize) -> Result<&mut Self, TableError> {
+        TableError::check_col(index, self.cols_len())?;
         for row in &mut self.data {
             row.remove(index);
         }
         self.cols -= 1;
         self.remove_empty_row_entries();
-        self
+        Ok(self)
     }
 
     /// Remove a column of entries at the specified column index, shifting trailing columns left.
     ///
     /// # Panics
-    /// Panics if `index` is outside the columns bounds.
+    /// Panics if `index` is outside the columns bounds. See [`Table::try_remove_col_par`].
     ///
     /// # Parallelisation
     /// Parallelised over the rows.
@@ -285,15 +382,26 @@ impl<T> Table<T> {
     where
         T: Send,
     {
-        if index >= self.cols_len() {
-            panic_cols(index, self.cols_len());
-        }
+        or_panic(self.try_remove_col_par(index))
+    }
+
+    /// Remove a column of entries at the specified column index, shifting trailing columns left.
+    ///
+    /// Returns an error if `index` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// Parallelised over the rows.
+    pub fn try_remove_col_par(&mut self, index: usize) -> Result<&mut Self, TableError>
+    where
+        T: Send,
+    {
+        TableError::check_col(index, self.cols_len())?;
         self.data
             .par_iter_mut()
             .for_each(|row| (row.remove(index), ()).1);
         self.cols -= 1;
         self.remove_empty_row_entries();
-        self
+        Ok(self)
     }
 
     /// Table has no data in it.
@@ -498,8 +606,8 @@ impl<T> Table<T> {
 
     /// Map each entry `Entry<T> -> Entry<U>` using a morphism `T -> U`.
     ///
-    /// The morphism only maps `Entry::Obj` variants; `Entry::Obj(T) -> Entry::Obj(U)`. `Nil` and
-    /// `Num` entries remain the same. For a mapping from an entry see [`Table::map`].
+    /// The morphism only maps `Entry::Obj` variants; `Entry::Obj(T) -> Entry::Obj(U)`. Other
+    /// entries remain the same. For a mapping from an entry see [`Table::map`].
     ///
     /// # Parallelisation
     /// `map_obj` preallocates the output table and does the mapping in a parallel fashion by row. This
@@ -512,11 +620,7 @@ impl<T> Table<T> {
         U: Send,
         F: Fn(T) -> U + Sync,
     {
-        self.map(|e| match e {
-            Nil => Nil,
-            Num(x) => Num(x),
-            Obj(t) => Obj(f(t)),
-        })
+        self.map(|e| e.map_obj(&f))
     }
 
     /// Map each entry `&Entry<T> -> Entry<U>` using a morphism `&Entry<T> -> Entry<U>`.
@@ -544,8 +648,8 @@ impl<T> Table<T> {
 
     /// Map each entry `&Entry<T> -> Entry<U>` using a morphism `&T -> U`.
     ///
-    /// The morphism only maps `Entry::Obj` variants; `Entry::Obj(T) -> Entry::Obj(U)`. `Nil` and
-    /// `Num` entries remain the same. For a mapping from an entry see [`Table::map_ref`].
+    /// The morphism only maps `Entry::Obj` variants; `Entry::Obj(T) -> Entry::Obj(U)`. Other
+    /// entries remain the same. For a mapping from an entry see [`Table::map_ref`].
     ///
     /// # Parallelisation
     /// `map_ref_obj` preallocates the output table and does the mapping in a parallel fashion by row.
@@ -556,11 +660,7 @@ impl<T> Table<T> {
         U: Send,
         F: Fn(&T) -> U + Sync,
     {
-        self.map_ref(|e| match e {
-            Nil => Nil,
-            Num(x) => Num(*x),
-            Obj(t) => Obj(f(t)),
-        })
+        self.map_ref(|e| e.as_ref().map_obj(&f))
     }
 
     /// Retain rows that match the predicate `p`.
@@ -610,12 +710,13 @@ impl<T> Table<T> {
     /// Sort _data_ rows by comparing entries in a column.
     ///
     /// Since [`Entry`] does not implement [`Ord`] (as there is no ordering between variants), the
-    /// caller must define the ordering between entries.
+    /// caller must define the ordering between entries. [`Entry::total_cmp`] can be used as a
+    /// default ordering. To sort by multiple columns, see [`Table::sort_by_keys`].
     ///
     /// [`Table::sort`] is a _stable_ sort.
     ///
     /// # Panics
-    /// Panics if `col` is outside the columns bounds.
+    /// Panics if `col` is outside the columns bounds. See [`Table::try_sort`].
     ///
     /// # Parallelisation
     /// `sort` uses parallelisation to efficiently sort table.
@@ -624,19 +725,35 @@ impl<T> Table<T> {
         T: Send,
         F: Fn(&Entry<T>, &Entry<T>) -> std::cmp::Ordering + Sync,
     {
-        if col >= self.cols_len() {
-            panic_cols(col, self.cols_len());
-        }
+        or_panic(self.try_sort(col, ordering))
+    }
+
+    /// Sort _data_ rows by comparing entries in a column. See [`Table::sort`].
+    ///
+    /// Returns an error if `col` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// `try_sort` uses parallelisation to efficiently sort table.
+    pub fn try_sort<F>(&mut self, col: usize, ordering: F) -> Result<(), TableError>
+    where
+        T: Send,
+        F: Fn(&Entry<T>, &Entry<T>) -> std::cmp::Ordering + Sync,
+    {
+        TableError::check_col(col, self.cols_len())?;
 
         let s = if self.header { 1 } else { 0 };
-        let e = self.rows_len();
-        self.data[s..e].par_sort_by(|a, b| ordering(&a[col], &b[col]));
+        if let Some(data) = self.data.get_mut(s..) {
+            data.par_sort_by(|a, b| ordering(&a[col], &b[col]));
+        }
+        Ok(())
     }
 
     /// Reverse the order of the _data_ rows, in place.
     pub fn reverse_rows(&mut self) {
         let s = if self.header { 1 } else { 0 };
-        self.data[s..].reverse();
+        if let Some(data) = self.data.get_mut(s..) {
+            data.reverse();
+        }
     }
 
     /// Reverse the order of the columns, in place.
@@ -647,6 +764,133 @@ impl<T> Table<T> {
         self.data.par_iter_mut().for_each(|x| x.reverse());
     }
 
+    /// Transpose the table, turning rows into columns and columns into rows.
+    ///
+    /// Entries are _moved_, no cloning occurs. The header row becomes the first column, and the
+    /// first column becomes the header row; [`Table::header`] is left unchanged.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let mut r: Table<&str> = Table::new();
+    /// r.add_rows(
+    ///     vec![
+    ///         vec![Obj("a"), Obj("b"), Obj("c")].into_iter(),
+    ///         vec![Num(1.into()), Num(2.into()), Nil].into_iter()
+    ///     ].into_iter()
+    /// );
+    ///
+    /// let t = r.transpose();
+    /// assert_eq!((t.rows_len(), t.cols_len()), (3, 2));
+    /// let mut row = t.row(1).unwrap();
+    /// assert_eq!(row.next(), Some(&Obj("b")));
+    /// assert_eq!(row.next(), Some(&Num(2.into())));
+    /// ```
+    pub fn transpose(self) -> Self {
+        let rows = self.rows_len();
+        let data = transpose_vecs(self.data, self.cols);
+        Table {
+            cols: if data.is_empty() { 0 } else { rows },
+            data,
+            header: self.header,
+        }
+    }
+
+    /// Transpose the table, turning rows into columns and columns into rows.
+    ///
+    /// See [`Table::transpose`].
+    ///
+    /// # Parallelisation
+    /// `transpose_par` splits the rows into bands of columns in parallel, and then transposes each
+    /// band in parallel. Entries are still _moved_, not cloned.
+    pub fn transpose_par(self) -> Self
+    where
+        T: Send,
+    {
+        let (rows, cols) = (self.rows_len(), self.cols_len());
+        if rows == 0 || cols == 0 {
+            return self.transpose();
+        }
+
+        let band = max(cols / rayon::current_num_threads(), 1);
+        let bands = cols.div_ceil(band);
+
+        let split = self
+            .data
+            .into_par_iter()
+            .map(|row| {
+                let mut row = row.into_iter();
+                (0..bands)
+                    .map(|_| row.by_ref().take(band).collect::<Vec<_>>())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut grouped = repeat_with(|| Vec::with_capacity(rows))
+            .take(bands)
+            .collect::<Vec<_>>();
+        for row in split {
+            grouped
+                .iter_mut()
+                .zip(row)
+                .for_each(|(g, piece)| g.push(piece));
+        }
+
+        let data = grouped
+            .into_par_iter()
+            .flat_map_iter(|pieces| {
+                let width = pieces.first().map(|x| x.len()).unwrap_or_default();
+                transpose_vecs(pieces, width)
+            })
+            .collect();
+
+        Table {
+            data,
+            header: self.header,
+            cols: rows,
+        }
+    }
+
+    /// Transpose the table, using column `col` as the new header row.
+    ///
+    /// The column `col` is moved to the front before transposing, such that it becomes the header
+    /// row and the old header row becomes the first column. [`Table::header`] is set to `true`.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let mut r: Table<&str> = Table::new();
+    /// r.add_rows(
+    ///     vec![
+    ///         vec![Obj("metric"), Obj("name")].into_iter(),
+    ///         vec![Num(1.into()), Obj("a")].into_iter(),
+    ///         vec![Num(2.into()), Obj("b")].into_iter(),
+    ///     ].into_iter()
+    /// );
+    ///
+    /// let t = r.transpose_with_header(1);
+    /// let mut header = t.row(0).unwrap();
+    /// assert_eq!(header.next(), Some(&Obj("name")));
+    /// assert_eq!(header.next(), Some(&Obj("a")));
+    /// assert_eq!(header.next(), Some(&Obj("b")));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds.
+    pub fn transpose_with_header(mut self, col: usize) -> Self {
+        if col >= self.cols_len() {
+            panic_cols(col, self.cols_len());
+        }
+
+        for row in &mut self.data {
+            row[..=col].rotate_right(1);
+        }
+
+        let mut table = self.transpose();
+        table.header = true;
+        table
+    }
+
     /// Extracts the backing vector of the Table.
     pub fn into_raw(self) -> Vec<Vec<Entry<T>>> {
         self.data
@@ -713,10 +957,31 @@ fn alloc<U>(rows: usize, cols: usize) -> Vec<Vec<Entry<U>>> {
         .collect()
 }
 
+/// Transposes `rows`, each expected to be `cols` long, by _moving_ the entries.
+fn transpose_vecs<U>(rows: Vec<Vec<Entry<U>>>, cols: usize) -> Vec<Vec<Entry<U>>> {
+    let mut rows = rows.into_iter().map(Vec::into_iter).collect::<Vec<_>>();
+    (0..cols)
+        .map(|_| {
+            rows.iter_mut()
+                .map(|x| x.next().unwrap_or_default())
+                .collect()
+        })
+        .collect()
+}
+
 fn panic_rows(idx: usize, rows: usize) {
-    panic!("index {} is outside bounds of table rows {}", idx, rows);
+    panic!("{}", TableError::RowOutOfBounds { index: idx, rows });
 }
 
 fn panic_cols(idx: usize, cols: usize) {
-    panic!("index {} is outside bounds of table columns {}", idx, cols);
+    panic!("{}", TableError::ColOutOfBounds { index: idx, cols });
+}
+
+/// Unwraps the result of a `try_*` method, panicking with the error message.
+#[track_caller]
+fn or_panic<U>(result: Result<U, TableError>) -> U {
+    match result {
+        Ok(x) => x,
+        Err(e) => panic!("{}", e),
+    }
 }
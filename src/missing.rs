@@ -0,0 +1,383 @@
+use super::*;
+
+impl<T> Table<T> {
+    /// Replace [`Nil`] _data_ entries in column `col` with `value`.
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds. See [`Table::try_fill_nil`].
+    pub fn fill_nil(&mut self, col: usize, value: Entry<T>) -> &mut Self
+    where
+        T: Clone,
+    {
+        or_panic(self.try_fill_nil(col, value))
+    }
+
+    /// Replace [`Nil`] _data_ entries in column `col` with `value`.
+    ///
+    /// Returns an error if `col` is outside the columns bounds.
+    pub fn try_fill_nil(&mut self, col: usize, value: Entry<T>) -> Result<&mut Self, TableError>
+    where
+        T: Clone,
+    {
+        self.nil_col_mut(col)?
+            .filter(|e| e.is_nil())
+            .for_each(|e| *e = value.clone());
+        Ok(self)
+    }
+
+    /// Replace [`Nil`] _data_ entries in column `col` with the last non-nil entry above it.
+    ///
+    /// If `limit` is specified, at most `limit` consecutive nils are filled. Nils without a
+    /// preceding entry remain nil.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let mut r: Table<()> = Table::new();
+    /// r.set_header(false);
+    /// r.add_col(vec![Nil, Num(1.into()), Nil, Nil, Num(2.into()), Nil].into_iter());
+    ///
+    /// r.fill_nil_forward(0, Some(1));
+    /// let col: Vec<_> = r.col(0).unwrap().cloned().collect();
+    /// assert_eq!(col, vec![Nil, Num(1.into()), Num(1.into()), Nil, Num(2.into()), Num(2.into())]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds. See [`Table::try_fill_nil_forward`].
+    pub fn fill_nil_forward(&mut self, col: usize, limit: Option<usize>) -> &mut Self
+    where
+        T: Clone,
+    {
+        or_panic(self.try_fill_nil_forward(col, limit))
+    }
+
+    /// Replace [`Nil`] _data_ entries in column `col` with the last non-nil entry above it. See
+    /// [`Table::fill_nil_forward`].
+    ///
+    /// Returns an error if `col` is outside the columns bounds.
+    pub fn try_fill_nil_forward(
+        &mut self,
+        col: usize,
+        limit: Option<usize>,
+    ) -> Result<&mut Self, TableError>
+    where
+        T: Clone,
+    {
+        fill_dir(self.nil_col_mut(col)?, limit);
+        Ok(self)
+    }
+
+    /// Replace [`Nil`] _data_ entries in column `col` with the next non-nil entry below it.
+    ///
+    /// If `limit` is specified, at most `limit` consecutive nils are filled. Nils without a
+    /// following entry remain nil.
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds. See [`Table::try_fill_nil_backward`].
+    pub fn fill_nil_backward(&mut self, col: usize, limit: Option<usize>) -> &mut Self
+    where
+        T: Clone,
+    {
+        or_panic(self.try_fill_nil_backward(col, limit))
+    }
+
+    /// Replace [`Nil`] _data_ entries in column `col` with the next non-nil entry below it. See
+    /// [`Table::fill_nil_backward`].
+    ///
+    /// Returns an error if `col` is outside the columns bounds.
+    pub fn try_fill_nil_backward(
+        &mut self,
+        col: usize,
+        limit: Option<usize>,
+    ) -> Result<&mut Self, TableError>
+    where
+        T: Clone,
+    {
+        fill_dir(self.nil_col_mut(col)?.rev(), limit);
+        Ok(self)
+    }
+
+    /// Linearly interpolate runs of [`Nil`] _data_ entries in column `col` which lie between two
+    /// `Num` entries.
+    ///
+    /// Interpolation is by row position. Nils which are not bounded by `Num` entries on both sides
+    /// remain nil.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let mut r: Table<()> = Table::new();
+    /// r.set_header(false);
+    /// r.add_col(vec![Nil, Num(1.into()), Nil, Nil, Num(4.into()), Nil].into_iter());
+    ///
+    /// r.interpolate_nil(0);
+    /// let col: Vec<_> = r.col(0).unwrap().cloned().collect();
+    /// assert_eq!(col, vec![Nil, Num(1.into()), Num(2.into()), Num(3.into()), Num(4.into()), Nil]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds. See [`Table::try_interpolate_nil`].
+    pub fn interpolate_nil(&mut self, col: usize) -> &mut Self {
+        or_panic(self.try_interpolate_nil(col))
+    }
+
+    /// Linearly interpolate runs of [`Nil`] _data_ entries in column `col`. See
+    /// [`Table::interpolate_nil`].
+    ///
+    /// Returns an error if `col` is outside the columns bounds.
+    pub fn try_interpolate_nil(&mut self, col: usize) -> Result<&mut Self, TableError> {
+        let mut entries = self.nil_col_mut(col)?.collect::<Vec<_>>();
+
+        // index and value of the last Num entry, if only nils follow it
+        let mut start: Option<(usize, f64)> = None;
+        for i in 0..entries.len() {
+            match &*entries[i] {
+                Nil => (),
+                Num(n) => {
+                    let y1 = n.as_f64();
+                    if let Some((x0, y0)) = start.filter(|&(x0, _)| x0 + 1 < i) {
+                        let m = (y1 - y0) / (i - x0) as f64;
+                        for (x, e) in entries[x0 + 1..i].iter_mut().enumerate() {
+                            **e = num::float(y0 + m * (x + 1) as f64).map_or(Nil, Num);
+                        }
+                    }
+                    start = Some((i, y1));
+                }
+                _ => start = None,
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// Remove _data_ rows which have more than `max` [`Nil`] entries.
+    pub fn drop_nil_rows(&mut self, max: usize) -> &mut Self {
+        let header = self.header;
+        self.retain_rows(|i, row| (header && i == 0) || row.filter(|e| e.is_nil()).count() <= max);
+        self
+    }
+
+    /// Remove columns which have more than `max` [`Nil`] _data_ entries.
+    ///
+    /// # Parallelisation
+    /// Nils are counted and columns are removed in parallel over the rows.
+    pub fn drop_nil_cols(&mut self, max: usize) -> &mut Self
+    where
+        T: Send + Sync,
+    {
+        let s = if self.header { 1 } else { 0 };
+        let cols = self.cols_len();
+        let nils = self
+            .data
+            .get(s..)
+            .unwrap_or_default()
+            .par_iter()
+            .fold(
+                || vec![0usize; cols],
+                |mut acc, row| {
+                    acc.iter_mut()
+                        .zip(row)
+                        .filter(|x| x.1.is_nil())
+                        .for_each(|x| *x.0 += 1);
+                    acc
+                },
+            )
+            .reduce(
+                || vec![0; cols],
+                |mut a, b| {
+                    a.iter_mut().zip(b).for_each(|x| *x.0 += x.1);
+                    a
+                },
+            );
+
+        let keep = nils.iter().map(|&n| n <= max).collect::<Vec<_>>();
+        if keep.iter().all(|&k| k) {
+            return self;
+        }
+
+        self.data.par_iter_mut().for_each(|row| {
+            let mut keep = keep.iter();
+            row.retain(|_| keep.next().copied().unwrap_or_default());
+        });
+        self.cols = keep.iter().filter(|&&k| k).count();
+        self.remove_empty_row_entries();
+        self
+    }
+
+    /// The mutable _data_ entries of column `col`, checking bounds.
+    fn nil_col_mut(
+        &mut self,
+        col: usize,
+    ) -> Result<impl DoubleEndedIterator<Item = &mut Entry<T>>, TableError> {
+        TableError::check_col(col, self.cols_len())?;
+        let s = if self.header { 1 } else { 0 };
+        Ok(self
+            .data
+            .iter_mut()
+            .skip(s)
+            .filter_map(move |x| x.get_mut(col)))
+    }
+}
+
+fn fill_dir<'a, T, I>(entries: I, limit: Option<usize>)
+where
+    T: 'a + Clone,
+    I: Iterator<Item = &'a mut Entry<T>>,
+{
+    let limit = limit.unwrap_or(usize::MAX);
+    let mut last: Option<Entry<T>> = None;
+    let mut run = 0;
+    for e in entries {
+        match (&*e, &last) {
+            (Nil, Some(x)) if run < limit => {
+                *e = x.clone();
+                run += 1;
+            }
+            (Nil, _) => (),
+            (x, _) => {
+                last = Some(x.clone());
+                run = 0;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n(x: i32) -> Entry<&'static str> {
+        Num(x.into())
+    }
+
+    fn table() -> Table<&'static str> {
+        Table::from(vec![
+            vec![Obj("a"), Obj("b"), Obj("c")],
+            vec![Nil, n(1), Obj("x")],
+            vec![n(2), Nil, Nil],
+            vec![Nil, Nil, Obj("z")],
+            vec![Nil, n(4), Obj("y")],
+            vec![n(5), Nil, Nil],
+        ])
+    }
+
+    fn col(t: &Table<&'static str>, c: usize) -> Vec<Entry<&'static str>> {
+        t.col(c).unwrap().cloned().collect()
+    }
+
+    #[test]
+    fn fill_constant() {
+        let mut t = table();
+        t.fill_nil(0, Obj("z"));
+        assert_eq!(
+            col(&t, 0),
+            vec![Obj("a"), Obj("z"), n(2), Obj("z"), Obj("z"), n(5)]
+        );
+
+        let mut t = table();
+        t.set_header(false);
+        t.fill_nil(2, n(0));
+        assert_eq!(
+            col(&t, 2),
+            vec![Obj("c"), Obj("x"), n(0), Obj("z"), Obj("y"), n(0)]
+        );
+    }
+
+    #[test]
+    fn fill_forward_backward() {
+        let mut t = table();
+        t.fill_nil_forward(0, None).fill_nil_forward(2, Some(1));
+        assert_eq!(col(&t, 0), vec![Obj("a"), Nil, n(2), n(2), n(2), n(5)]);
+        assert_eq!(
+            col(&t, 2),
+            vec![Obj("c"), Obj("x"), Obj("x"), Obj("z"), Obj("y"), Obj("y")]
+        );
+
+        let mut t = table();
+        t.fill_nil_backward(0, None).fill_nil_backward(1, Some(1));
+        assert_eq!(col(&t, 0), vec![Obj("a"), n(2), n(2), n(5), n(5), n(5)]);
+        assert_eq!(col(&t, 1), vec![Obj("b"), n(1), Nil, n(4), n(4), Nil]);
+
+        // header is not used as a fill value
+        let mut t = table();
+        t.fill_nil_forward(0, None);
+        assert_eq!(t.data[1][0], Nil);
+    }
+
+    #[test]
+    fn interpolate() {
+        let mut t = table();
+        t.interpolate_nil(0).interpolate_nil(1).interpolate_nil(2);
+        assert_eq!(col(&t, 0), vec![Obj("a"), Nil, n(2), n(3), n(4), n(5)]);
+        assert_eq!(col(&t, 1), vec![Obj("b"), n(1), n(2), n(3), n(4), Nil]);
+        assert_eq!(
+            col(&t, 2),
+            vec![Obj("c"), Obj("x"), Nil, Obj("z"), Obj("y"), Nil]
+        );
+
+        let mut t: Table<()> = Table::from(vec![vec![
+            Num(1.into()),
+            Nil,
+            Num(2.into()),
+            Nil,
+            Obj(()),
+            Nil,
+            Num(3.into()),
+        ]])
+        .transpose();
+        t.set_header(false);
+        t.interpolate_nil(0);
+        assert_eq!(
+            t.col(0).unwrap().cloned().collect::<Vec<_>>(),
+            vec![
+                Num(1.into()),
+                Num(1.5.into()),
+                Num(2.into()),
+                Nil,
+                Obj(()),
+                Nil,
+                Num(3.into())
+            ]
+        );
+    }
+
+    #[test]
+    fn drop_rows_cols() {
+        let mut t = table();
+        t.drop_nil_rows(1);
+        assert_eq!(col(&t, 0), vec![Obj("a"), Nil, Nil]);
+
+        let mut t = table();
+        t.drop_nil_cols(2);
+        assert_eq!(t.cols_len(), 1);
+        assert_eq!(
+            col(&t, 0),
+            vec![Obj("c"), Obj("x"), Nil, Obj("z"), Obj("y"), Nil]
+        );
+
+        let mut t = table();
+        t.drop_nil_cols(0);
+        assert_eq!(t.cols_len(), 0);
+        assert!(t.is_empty());
+
+        let mut t = table();
+        t.drop_nil_cols(3);
+        assert_eq!(t, table());
+    }
+
+    #[test]
+    fn fill_nil_err() {
+        let mut t = table();
+        let err = TableError::ColOutOfBounds { index: 3, cols: 3 };
+        assert_eq!(t.try_fill_nil(3, Nil).err(), Some(err.clone()));
+        assert_eq!(t.try_fill_nil_backward(3, None).err(), Some(err.clone()));
+        assert_eq!(t.try_interpolate_nil(3).err(), Some(err));
+        assert_eq!(t, table());
+    }
+
+    #[test]
+    #[should_panic]
+    fn fill_nil_panic() {
+        table().fill_nil_forward(3, None);
+    }
+}
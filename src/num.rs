@@ -0,0 +1,111 @@
+//! Arithmetic over [`Number`]s.
+//!
+//! Integer arithmetic is used when both operands are integers and the result does not overflow,
+//! otherwise the operation falls back to floating point arithmetic. Results which are not finite
+//! (such as division by zero) return `None`.
+use ::kserd::Number;
+
+macro_rules! binary {
+    ($(#[$meta:meta])* $name:ident, $int:ident, $op:tt) => {
+        $(#[$meta])*
+        pub fn $name(a: Number, b: Number) -> Option<Number> {
+            match (int(a), int(b)) {
+                (Some(x), Some(y)) => x.$int(y).map(Number::from).or_else(|| float(a.as_f64() $op b.as_f64())),
+                _ => float(a.as_f64() $op b.as_f64()),
+            }
+        }
+    };
+}
+
+binary!(
+    /// `a + b`.
+    add, checked_add, +
+);
+binary!(
+    /// `a - b`.
+    sub, checked_sub, -
+);
+binary!(
+    /// `a * b`.
+    mul, checked_mul, *
+);
+binary!(
+    /// `a % b`. Returns `None` if `b` is zero.
+    rem, checked_rem, %
+);
+
+/// `a / b`. Integer division is only used if it is exact. Returns `None` if `b` is zero.
+pub fn div(a: Number, b: Number) -> Option<Number> {
+    match (int(a), int(b)) {
+        (Some(_), Some(0)) => None,
+        (Some(x), Some(y)) if x.checked_rem(y) == Some(0) => x.checked_div(y).map(Number::from),
+        _ => float(a.as_f64() / b.as_f64()),
+    }
+}
+
+/// `-a`.
+pub fn neg(a: Number) -> Option<Number> {
+    match int(a) {
+        Some(x) => x.checked_neg().map(Number::from),
+        None => float(-a.as_f64()),
+    }
+}
+
+/// Represent `a` as an integer, if it is _not_ a float.
+pub fn int(a: Number) -> Option<i128> {
+    match a {
+        Number::Float(_) => None,
+        a => a.as_i128().ok(),
+    }
+}
+
+/// A float `Number`, if `x` is finite.
+pub fn float(x: f64) -> Option<Number> {
+    if x.is_finite() {
+        Some(Number::from(x))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n<N: Into<Number>>(n: N) -> Number {
+        n.into()
+    }
+
+    #[test]
+    fn integer_arithmetic() {
+        assert_eq!(add(n(1), n(2)), Some(n(3)));
+        assert_eq!(sub(n(1), n(2)), Some(n(-1)));
+        assert_eq!(mul(n(-3), n(2)), Some(n(-6)));
+        assert_eq!(div(n(6), n(3)), Some(n(2)));
+        assert_eq!(div(n(7), n(2)), Some(n(3.5)));
+        assert_eq!(rem(n(7), n(2)), Some(n(1)));
+        assert_eq!(neg(n(7)), Some(n(-7)));
+        assert!(matches!(
+            div(n(6), n(3)),
+            Some(Number::Uint(_) | Number::Int(_))
+        ));
+    }
+
+    #[test]
+    fn float_arithmetic() {
+        assert_eq!(add(n(1.5), n(2)), Some(n(3.5)));
+        assert_eq!(mul(n(0.5), n(0.5)), Some(n(0.25)));
+        assert_eq!(neg(n(0.5)), Some(n(-0.5)));
+        assert_eq!(mul(n(i128::MAX), n(2)), Some(n(i128::MAX as f64 * 2.0)));
+        assert_eq!(div(n(i128::MIN), n(-1)), Some(n(-(i128::MIN as f64))));
+        assert_eq!(rem(n(i128::MIN), n(-1)), Some(n(0.0)));
+    }
+
+    #[test]
+    fn non_finite() {
+        assert_eq!(div(n(1), n(0)), None);
+        assert_eq!(div(n(1.0), n(0.0)), None);
+        assert_eq!(rem(n(1), n(0)), None);
+        assert_eq!(mul(n(f64::MAX), n(2)), None);
+    }
+}
@@ -0,0 +1,244 @@
+//! Arithmetic and comparison operators for [`Entry`].
+//!
+//! Numeric arithmetic uses [`num`], so integers stay integers where possible. `Nil` propagates
+//! through every operation, and operations between unsupported variants, or which overflow or
+//! divide by zero, also produce `Nil`.
+use super::*;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+macro_rules! binary {
+    ($(#[$meta:meta])* $trait:ident, $method:ident) => {
+        $(#[$meta])*
+        impl<T> $trait for Entry<T> {
+            type Output = Entry<T>;
+
+            fn $method(self, rhs: Self) -> Entry<T> {
+                $method(&self, &rhs).unwrap_or(Nil)
+            }
+        }
+
+        $(#[$meta])*
+        impl<T> $trait for &Entry<T> {
+            type Output = Entry<T>;
+
+            fn $method(self, rhs: Self) -> Entry<T> {
+                $method(self, rhs).unwrap_or(Nil)
+            }
+        }
+    };
+}
+
+binary!(
+    /// `Num + Num`, `Duration + Duration`, and `DateTime + Duration` in either order.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let x: Entry<&str> = Num(1.into()) + Num(2.into());
+    /// assert_eq!(x, Num(3.into()));
+    /// assert_eq!(x.clone() + Nil, Nil);
+    /// assert_eq!(x + Obj("a"), Nil);
+    ///
+    /// let dt: Entry<()> = DateTime("2020-01-31T10:00:00Z".parse().unwrap());
+    /// let d = Duration("PT1H".parse().unwrap());
+    /// assert_eq!(&dt + &d, DateTime("2020-01-31T11:00:00Z".parse().unwrap()));
+    /// ```
+    Add,
+    add
+);
+binary!(
+    /// `Num - Num`, `Duration - Duration`, `DateTime - Duration`, `DateTime - DateTime`, and
+    /// `Date - Date`, with the latter two producing a `Duration`.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let x: Entry<()> = Date("2020-03-01".parse().unwrap()) - Date("2020-02-01".parse().unwrap());
+    /// assert_eq!(x, Duration("P29D".parse().unwrap()));
+    /// ```
+    Sub,
+    sub
+);
+binary!(
+    /// `Num * Num`.
+    Mul,
+    mul
+);
+binary!(
+    /// `Num / Num`. Division by zero is `Nil`.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let x: Entry<()> = Num(3.into()) / Num(2.into());
+    /// assert_eq!(x, Num(1.5.into()));
+    /// assert_eq!(x / Num(0.into()), Nil);
+    /// ```
+    Div,
+    div
+);
+
+/// `-Num` and `-Duration`.
+impl<T> Neg for Entry<T> {
+    type Output = Entry<T>;
+
+    fn neg(self) -> Entry<T> {
+        -&self
+    }
+}
+
+/// `-Num` and `-Duration`.
+impl<T> Neg for &Entry<T> {
+    type Output = Entry<T>;
+
+    fn neg(self) -> Entry<T> {
+        let x = match self {
+            Num(x) => num::neg(*x).map(Num),
+            Duration(x) => x.checked_neg().map(Duration),
+            _ => None,
+        };
+        x.unwrap_or(Nil)
+    }
+}
+
+fn add<T>(a: &Entry<T>, b: &Entry<T>) -> Option<Entry<T>> {
+    match (a, b) {
+        (Num(x), Num(y)) => num::add(*x, *y).map(Num),
+        (Duration(x), Duration(y)) => x.checked_add(*y).map(Duration),
+        (DateTime(x), Duration(y)) | (Duration(y), DateTime(x)) => x.checked_add(*y).map(DateTime),
+        _ => None,
+    }
+}
+
+fn sub<T>(a: &Entry<T>, b: &Entry<T>) -> Option<Entry<T>> {
+    match (a, b) {
+        (Num(x), Num(y)) => num::sub(*x, *y).map(Num),
+        (Duration(x), Duration(y)) => x.checked_sub(*y).map(Duration),
+        (DateTime(x), Duration(y)) => x.checked_sub(*y).map(DateTime),
+        (DateTime(x), DateTime(y)) => Some(Duration(x.duration_since(*y))),
+        (Date(x), Date(y)) => Some(Duration(x.and_midnight().duration_since(y.and_midnight()))),
+        _ => None,
+    }
+}
+
+fn mul<T>(a: &Entry<T>, b: &Entry<T>) -> Option<Entry<T>> {
+    match (a, b) {
+        (Num(x), Num(y)) => num::mul(*x, *y).map(Num),
+        _ => None,
+    }
+}
+
+fn div<T>(a: &Entry<T>, b: &Entry<T>) -> Option<Entry<T>> {
+    match (a, b) {
+        (Num(x), Num(y)) => num::div(*x, *y).map(Num),
+        _ => None,
+    }
+}
+
+// Comparison of `Num` entries with primitive numbers. Other variants are unequal and unordered.
+macro_rules! primitive_cmp {
+    ($($t:ty)*) => {
+        $(
+            impl<T> PartialEq<$t> for Entry<T> {
+                fn eq(&self, rhs: &$t) -> bool {
+                    self.partial_cmp(rhs) == Some(Ordering::Equal)
+                }
+            }
+
+            impl<T> PartialOrd<$t> for Entry<T> {
+                fn partial_cmp(&self, rhs: &$t) -> Option<Ordering> {
+                    match self {
+                        Num(x) => x.partial_cmp(&Number::from(*rhs)),
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+primitive_cmp!(u8 u16 u32 u64 u128 usize i8 i16 i32 i64 i128 isize f32 f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type E = Entry<&'static str>;
+
+    fn n<N: Into<Number>>(x: N) -> E {
+        Num(x.into())
+    }
+
+    #[test]
+    fn numeric() {
+        assert_eq!(n(1) + n(2), n(3));
+        assert_eq!(n(1) - n(2.5), n(-1.5));
+        assert_eq!(n(3) * n(4), n(12));
+        assert_eq!(n(3) / n(4), n(0.75));
+        assert_eq!(n(8) / n(4), n(2));
+        assert_eq!(n(1) / n(0), Nil);
+        assert_eq!(-n(3), n(-3));
+        assert_eq!(&n(1) + &n(1), n(2));
+        assert_eq!(n(i64::MAX) + n(1), n(i64::MAX as i128 + 1));
+    }
+
+    #[test]
+    fn nil_and_objects() {
+        for op in [Add::add, Sub::sub, Mul::mul, Div::div] as [fn(E, E) -> E; 4] {
+            assert_eq!(op(Nil, n(1)), Nil);
+            assert_eq!(op(n(1), Nil), Nil);
+            assert_eq!(op(Obj("a"), n(1)), Nil);
+            assert_eq!(op(Bool(true), Bool(true)), Nil);
+        }
+        assert_eq!(-E::Nil, Nil);
+        assert_eq!(-Obj("a"), Nil);
+    }
+
+    #[test]
+    fn temporal() {
+        let dt = |s: &str| -> E { DateTime(s.parse().unwrap()) };
+        let dur = |s: &str| -> E { Duration(s.parse().unwrap()) };
+        let date = |s: &str| -> E { Date(s.parse().unwrap()) };
+
+        let a = dt("2020-01-31T10:00:00Z");
+        assert_eq!(&a + &dur("PT30M"), dt("2020-01-31T10:30:00Z"));
+        assert_eq!(dur("PT30M") + a.clone(), dt("2020-01-31T10:30:00Z"));
+        assert_eq!(&a - &dur("P1D"), dt("2020-01-30T10:00:00Z"));
+        assert_eq!(&a - &dt("2020-01-31T12:00:00Z"), dur("-PT2H"));
+        assert_eq!(dur("PT1H") - dur("PT2H"), dur("-PT1H"));
+        assert_eq!(-dur("PT1H"), dur("-PT1H"));
+        assert_eq!(date("2021-01-01") - date("2020-01-01"), dur("P366D"));
+        assert_eq!(date("2021-01-01") + dur("P1D"), Nil);
+        assert_eq!(&a * &dur("P1D"), Nil);
+        assert_eq!(dt("9999-12-31T23:00:00Z") + dur("PT1H"), Nil);
+    }
+
+    #[test]
+    fn primitive_comparisons() {
+        assert!(n(6) > 5);
+        assert!(n(6) >= 6u8);
+        assert!(n(5.5) < 6);
+        assert!(n(2) == 2.0);
+        assert!(n(2) != 3);
+        assert!(n(-1) < 0i64);
+        assert!(E::Nil != 0);
+        assert_eq!(Obj("a").partial_cmp(&1), None);
+        assert_eq!(E::Nil.partial_cmp(&0), None);
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(n(2).as_f64(), Some(2.0));
+        assert_eq!(n(2.5).as_i64(), None);
+        assert_eq!(n(u64::MAX).as_i64(), None);
+        assert_eq!(n(-4).as_i64(), Some(-4));
+        assert_eq!(Obj("1").as_f64(), None);
+
+        assert_eq!(i64::try_from(n(7)), Ok(7));
+        assert_eq!(Number::try_from(n(7)), Ok(7.into()));
+        assert_eq!(bool::try_from(E::Bool(true)), Ok(true));
+        assert_eq!(f64::try_from(E::Nil), Err(Nil));
+        let d: Result<time::Date, _> = Obj("2020-01-31").try_into();
+        assert_eq!(d, Err(Obj("2020-01-31")));
+    }
+}
@@ -0,0 +1,256 @@
+use super::*;
+
+impl<T> Table<T> {
+    /// Parallel iterator over rows, see [`Table::rows`].
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use rayon::prelude::*;
+    /// use Entry::*;
+    /// let r: Table<&str> = Table::from(vec![
+    ///     vec![Num(1.into()), Nil],
+    ///     vec![Nil, Nil],
+    ///     vec![Num(2.into()), Num(3.into())],
+    /// ]);
+    ///
+    /// let nils: Vec<usize> = r.par_rows().map(|row| row.filter(|e| e.is_nil()).count()).collect();
+    /// assert_eq!(nils, vec![1, 2, 0]);
+    /// let nils: Vec<usize> = r.par_cols().map(|col| col.filter(|e| e.is_nil()).count()).collect();
+    /// assert_eq!(nils, vec![1, 2]);
+    /// ```
+    pub fn par_rows(
+        &self,
+    ) -> impl IndexedParallelIterator<Item = impl Iterator<Item = &Entry<T>>> + '_
+    where
+        T: Sync,
+    {
+        self.data.par_iter().map(|x| x.iter())
+    }
+
+    /// Parallel iterator over columns, see [`Table::cols`].
+    pub fn par_cols(
+        &self,
+    ) -> impl IndexedParallelIterator<Item = impl Iterator<Item = &Entry<T>>> + '_
+    where
+        T: Sync,
+    {
+        (0..self.cols_len())
+            .into_par_iter()
+            .map(move |i| self.col(i).unwrap())
+    }
+
+    /// Map each row to a new row using `f`, which is supplied the row index and the row entries.
+    ///
+    /// The header row, if any, is included as row 0 and the header flag is kept. Rows may map to
+    /// differing lengths, shorter rows are padded with [`Nil`]. The entries of neighbouring rows
+    /// can be read through the table itself.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let r: Table<&str> = Table::from(vec![
+    ///     vec![Obj("day"), Obj("demand")],
+    ///     vec![Num(1.into()), Num(10.into())],
+    ///     vec![Num(2.into()), Num(15.into())],
+    ///     vec![Num(3.into()), Num(12.into())],
+    /// ]);
+    ///
+    /// // change from the previous day
+    /// let change = r.par_map_rows(|i, row| match i {
+    ///     0 => vec![row[0].clone(), Obj("change")],
+    ///     1 => vec![row[0].clone(), Nil],
+    ///     i => vec![row[0].clone(), &row[1] - &r[(i - 1, 1)]],
+    /// });
+    ///
+    /// assert_eq!(
+    ///     change.col(1).unwrap().cloned().collect::<Vec<_>>(),
+    ///     vec![Obj("change"), Nil, Num(5.into()), Num((-3).into())]
+    /// );
+    /// ```
+    ///
+    /// # Parallelisation
+    /// Rows are mapped in parallel.
+    pub fn par_map_rows<U, F>(&self, f: F) -> Table<U>
+    where
+        T: Sync,
+        U: Send,
+        F: Fn(usize, &[Entry<T>]) -> Vec<Entry<U>> + Sync,
+    {
+        let mut data = Vec::with_capacity(self.rows_len());
+        data.par_extend(self.data.par_iter().enumerate().map(|(i, row)| f(i, row)));
+        let mut table = Table::from(data);
+        table.header = self.header;
+        table
+    }
+
+    /// Map each entry of column `col` using `f`, which is supplied the row index and the entry.
+    ///
+    /// The header row, if any, is included as row 0. The results are returned in row order.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let r: Table<&str> = Table::from(vec![
+    ///     vec![Obj("demand")],
+    ///     vec![Num(10.into())],
+    ///     vec![Nil],
+    /// ]);
+    ///
+    /// let labels = r.par_map_col(0, |i, e| format!("{}: {}", i, e.as_str()));
+    /// assert_eq!(labels, vec!["0: demand", "1: 10", "2: -"]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// Entries are mapped in parallel.
+    pub fn par_map_col<U, F>(&self, col: usize, f: F) -> Vec<U>
+    where
+        T: Sync,
+        U: Send,
+        F: Fn(usize, &Entry<T>) -> U + Sync,
+    {
+        or_panic(TableError::check_col(col, self.cols_len()));
+        let mut v = Vec::with_capacity(self.rows_len());
+        v.par_extend(
+            self.data
+                .par_iter()
+                .enumerate()
+                .map(|(i, row)| f(i, &row[col])),
+        );
+        v
+    }
+
+    /// Mutate each entry of column `col` in place using `f`, which is supplied the row index and
+    /// the entry.
+    ///
+    /// The header row, if any, is included as row 0.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let mut r: Table<&str> = Table::from(vec![
+    ///     vec![Obj("demand")],
+    ///     vec![Num(10.into())],
+    ///     vec![Nil],
+    /// ]);
+    ///
+    /// r.apply_col(0, |i, e| if i > 0 && e.is_nil() { *e = Num(0.into()) });
+    /// assert_eq!(r[(2, 0)], Num(0.into()));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds. See [`Table::try_apply_col`].
+    ///
+    /// # Parallelisation
+    /// Entries are mutated in parallel.
+    pub fn apply_col<F>(&mut self, col: usize, f: F) -> &mut Self
+    where
+        T: Send,
+        F: Fn(usize, &mut Entry<T>) + Sync,
+    {
+        or_panic(self.try_apply_col(col, f))
+    }
+
+    /// Mutate each entry of column `col` in place using `f`. See [`Table::apply_col`].
+    ///
+    /// Returns an error if `col` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// Entries are mutated in parallel.
+    pub fn try_apply_col<F>(&mut self, col: usize, f: F) -> Result<&mut Self, TableError>
+    where
+        T: Send,
+        F: Fn(usize, &mut Entry<T>) + Sync,
+    {
+        TableError::check_col(col, self.cols_len())?;
+        self.data
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(i, row)| f(i, &mut row[col]));
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n(x: i32) -> Entry<&'static str> {
+        Num(x.into())
+    }
+
+    fn table() -> Table<&'static str> {
+        Table::from(vec![
+            vec![Obj("a"), Obj("b")],
+            vec![n(1), n(2)],
+            vec![n(3), Nil],
+            vec![n(5), n(6)],
+        ])
+    }
+
+    #[test]
+    fn par_iters() {
+        let t = table();
+        let rows: Vec<Vec<_>> = t.par_rows().map(|r| r.cloned().collect()).collect();
+        assert_eq!(rows, t.clone().into_raw());
+        let cols: Vec<Vec<_>> = t.par_cols().map(|c| c.cloned().collect()).collect();
+        assert_eq!(
+            cols,
+            t.cols()
+                .map(|c| c.cloned().collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Table::<()>::new().par_cols().count(), 0);
+    }
+
+    #[test]
+    fn map_rows() {
+        let t = table();
+        let m = t.par_map_rows(|i, row| match i {
+            0 => row.to_vec(),
+            _ => row.iter().map(|e| e + &t[(i - 1, 0)]).collect(),
+        });
+        assert!(m.header);
+        assert_eq!(
+            m.into_raw(),
+            vec![
+                vec![Obj("a"), Obj("b")],
+                vec![Nil, Nil],
+                vec![n(4), Nil],
+                vec![n(8), n(9)],
+            ]
+        );
+
+        // ragged rows are padded
+        let m: Table<()> = t.par_map_rows(|i, _| vec![Nil; i]);
+        assert_eq!((m.rows_len(), m.cols_len()), (4, 3));
+    }
+
+    #[test]
+    fn map_and_apply_col() {
+        let mut t = table();
+        assert_eq!(
+            t.par_map_col(1, |i, e| (i, e.is_nil())),
+            vec![(0, false), (1, false), (2, true), (3, false)]
+        );
+
+        t.apply_col(1, |i, e| {
+            if e.is_nil() {
+                *e = Num(i.into());
+            }
+        });
+        assert_eq!(t.col(1).unwrap().nth(2), Some(&n(2)));
+        assert_eq!(
+            t.try_apply_col(2, |_, _| ()).err(),
+            Some(TableError::ColOutOfBounds { index: 2, cols: 2 })
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn map_col_panic() {
+        table().par_map_col(2, |_, _| ());
+    }
+}
@@ -105,16 +105,32 @@ fn quoted_str(line: &[u8], delimiter: u8, quot: u8) -> (&[u8], &[u8]) {
     (i, &[])
 }
 
-fn map_entry(s: &str) -> Entry<&str> {
+pub(crate) fn map_entry(s: &str) -> Entry<&str> {
     if s.is_empty() {
         Entry::Nil
     } else if let Ok(x) = s.parse::<Number>() {
         Entry::Num(x)
+    } else if let Some(x) = map_typed(s) {
+        x
     } else {
         Entry::Obj(s)
     }
 }
 
+/// Detect booleans and ISO-8601 dates, date-times, and durations.
+///
+/// The leading bytes are checked before parsing so most objects are rejected cheaply.
+fn map_typed<'a>(s: &str) -> Option<Entry<&'a str>> {
+    match s.as_bytes() {
+        [b'0'..=b'9', _, _, _, b'-', ..] if s.len() == 10 => s.parse().ok().map(Entry::Date),
+        [b'0'..=b'9', _, _, _, b'-', ..] => s.parse().ok().map(Entry::DateTime),
+        [b'P', ..] | [b'-', b'P', ..] => s.parse().ok().map(Entry::Duration),
+        _ if s.eq_ignore_ascii_case("true") => Some(Entry::Bool(true)),
+        _ if s.eq_ignore_ascii_case("false") => Some(Entry::Bool(false)),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::parse_dsv as parse;
@@ -169,6 +185,44 @@ lion hyena elephant";
         assert_eq!(parse(',', s), repr);
     }
 
+    #[test]
+    fn typed() {
+        let s = "true,2020-01-31,2020-01-31T10:00:00Z,P1DT2H
+FALSE,2020-02-30,2020-01-31 10:00:00+01:00,-PT0.5S
+truthy,2020-1-31,2020-01-31T10:00,Price";
+
+        let d = |s: &str| Date(s.parse().unwrap());
+        let dt = |s: &str| DateTime(s.parse().unwrap());
+        let dur = |s: &str| Duration(s.parse().unwrap());
+        let mut repr = Table::new();
+        repr.add_rows(
+            vec![
+                vec![
+                    Bool(true),
+                    d("2020-01-31"),
+                    dt("2020-01-31T10:00:00Z"),
+                    dur("P1DT2H"),
+                ],
+                vec![
+                    Bool(false),
+                    Obj("2020-02-30"),
+                    dt("2020-01-31T09:00:00Z"),
+                    dur("-PT0.5S"),
+                ],
+                vec![
+                    Obj("truthy"),
+                    Obj("2020-1-31"),
+                    dt("2020-01-31T10:00:00Z"),
+                    Obj("Price"),
+                ],
+            ]
+            .into_iter()
+            .map(|x| x.into_iter()),
+        );
+
+        assert_eq!(parse(',', s), repr);
+    }
+
     #[test]
     fn test_blanks() {
         let s = "Hello,,world
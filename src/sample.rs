@@ -0,0 +1,358 @@
+use super::*;
+use std::{collections::HashMap, hash::Hash};
+
+impl<T: Clone + Send + Sync> Table<T> {
+    /// A random sample of `n` _data_ rows, without replacement.
+    ///
+    /// If there are fewer than `n` data rows, all are returned. The sampled rows keep their
+    /// relative order and the header row is retained. The same `seed` gives the same sample.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// # use std::iter::once;
+    /// use Entry::*;
+    /// let mut r: Table<&str> = Table::new();
+    /// r.add_row(once(Obj("id")));
+    /// r.add_rows((0..100).map(|i| once(Num(i.into()))));
+    ///
+    /// let s = r.sample(10, 42);
+    /// assert_eq!(s.rows_len(), 11);
+    /// assert_eq!(s[(0, 0)], Obj("id"));
+    /// assert_eq!(s, r.sample(10, 42));
+    /// assert_ne!(s, r.sample(10, 43));
+    /// ```
+    ///
+    /// # Parallelisation
+    /// Sampled rows are cloned in parallel.
+    pub fn sample(&self, n: usize, seed: u64) -> Table<T> {
+        let mut rows = Rng::new(seed).choose(self.data_len(), n);
+        rows.sort_unstable();
+        self.select_data_rows(&rows)
+    }
+
+    /// A random sample of a fraction `frac` of the _data_ rows, without replacement.
+    ///
+    /// The number of rows is rounded to the nearest whole row. See [`Table::sample`].
+    ///
+    /// # Panics
+    /// Panics if `frac` is not within `0.0..=1.0`.
+    ///
+    /// # Parallelisation
+    /// Sampled rows are cloned in parallel.
+    pub fn sample_frac(&self, frac: f64, seed: u64) -> Table<T> {
+        self.sample(frac_of(frac, self.data_len()), seed)
+    }
+
+    /// A random sample of a fraction `frac` of the _data_ rows of each distinct entry in column
+    /// `col`, without replacement.
+    ///
+    /// Each stratum's count is rounded to the nearest whole row, so every key keeps roughly its
+    /// share of the rows. The sampled rows keep their relative order and the header row is
+    /// retained. The same `seed` gives the same sample.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let mut r: Table<&str> = Table::new();
+    /// r.add_row(vec![Obj("cut"), Obj("price")].into_iter());
+    /// r.add_rows((0..100).map(|i| {
+    ///     let cut = if i % 4 == 0 { "Fair" } else { "Ideal" };
+    ///     vec![Obj(cut), Num(i.into())].into_iter()
+    /// }));
+    ///
+    /// let s = r.sample_stratified(0, 0.2, 7);
+    /// let fair = s.col(0).unwrap().filter(|e| **e == Obj("Fair")).count();
+    /// assert_eq!((s.rows_len() - 1, fair), (20, 5));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds, or `frac` is not within `0.0..=1.0`.
+    ///
+    /// # Parallelisation
+    /// Entries are grouped and sampled rows cloned in parallel.
+    pub fn sample_stratified(&self, col: usize, frac: f64, seed: u64) -> Table<T>
+    where
+        T: Hash + Eq,
+    {
+        or_panic(TableError::check_col(col, self.cols_len()));
+        frac_of(frac, 0);
+        let s = if self.header { 1 } else { 0 };
+        let data = self.data.get(s..).unwrap_or_default();
+
+        let mut strata = data
+            .par_iter()
+            .enumerate()
+            .fold(HashMap::new, |mut map: HashMap<_, Vec<_>>, (i, row)| {
+                map.entry(&row[col]).or_default().push(i);
+                map
+            })
+            .reduce(HashMap::new, |mut a, b| {
+                // b follows a in row order
+                for (e, rows) in b {
+                    a.entry(e).or_default().extend(rows);
+                }
+                a
+            })
+            .into_values()
+            .collect::<Vec<_>>();
+        // sample strata in order of first occurrence, so the sample only depends on the seed
+        strata.sort_unstable_by_key(|rows| rows[0]);
+
+        let mut rng = Rng::new(seed);
+        let mut rows = strata
+            .into_iter()
+            .flat_map(|stratum| {
+                rng.choose(stratum.len(), frac_of(frac, stratum.len()))
+                    .into_iter()
+                    .map(move |i| stratum[i])
+            })
+            .collect::<Vec<_>>();
+        rows.sort_unstable();
+        self.select_data_rows(&rows)
+    }
+
+    /// Randomly split the _data_ rows into two tables, the first with a fraction `frac` of the
+    /// rows and the second with the remainder.
+    ///
+    /// The number of rows in the first table is rounded to the nearest whole row. Rows keep their
+    /// relative order and both tables retain the header row. The same `seed` gives the same
+    /// split.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// # use std::iter::once;
+    /// use Entry::*;
+    /// let mut r: Table<&str> = Table::new();
+    /// r.add_row(once(Obj("id")));
+    /// r.add_rows((0..10).map(|i| once(Num(i.into()))));
+    ///
+    /// let (train, test) = r.split(0.8, 1);
+    /// assert_eq!((train.rows_len(), test.rows_len()), (9, 3));
+    /// assert_eq!(test[(0, 0)], Obj("id"));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `frac` is not within `0.0..=1.0`.
+    ///
+    /// # Parallelisation
+    /// Rows are cloned in parallel.
+    pub fn split(&self, frac: f64, seed: u64) -> (Table<T>, Table<T>) {
+        let len = self.data_len();
+        let mut rows = Rng::new(seed).choose(len, len);
+        let (a, b) = rows.split_at_mut(frac_of(frac, len));
+        a.sort_unstable();
+        b.sort_unstable();
+        (self.select_data_rows(a), self.select_data_rows(b))
+    }
+
+    /// Table of the header row, if any, followed by the _data_ rows `rows`.
+    pub(crate) fn select_data_rows(&self, rows: &[usize]) -> Table<T> {
+        let s = if self.header {
+            min(1, self.rows_len())
+        } else {
+            0
+        };
+        let mut data = Vec::with_capacity(rows.len() + s);
+        data.par_extend(
+            (0..s)
+                .into_par_iter()
+                .chain(rows.par_iter().map(|i| i + s))
+                .map(|i| self.data[i].clone()),
+        );
+        Table {
+            data,
+            cols: self.cols,
+            header: self.header,
+        }
+    }
+}
+
+impl<T> Table<T> {
+    /// Randomly shuffle the _data_ rows, in place. The same `seed` gives the same order.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// # use std::iter::once;
+    /// use Entry::*;
+    /// let mut r: Table<&str> = Table::new();
+    /// r.add_row(once(Obj("id")));
+    /// r.add_rows((0..10).map(|i| once(Num(i.into()))));
+    ///
+    /// let mut s = r.clone();
+    /// s.shuffle_rows(3);
+    /// assert_eq!(s[(0, 0)], Obj("id"));
+    /// assert_ne!(s, r);
+    ///
+    /// s.sort(0, Entry::total_cmp);
+    /// assert_eq!(s, r);
+    /// ```
+    pub fn shuffle_rows(&mut self, seed: u64) {
+        let s = if self.header { 1 } else { 0 };
+        if let Some(data) = self.data.get_mut(s..) {
+            let mut rng = Rng::new(seed);
+            for i in (1..data.len()).rev() {
+                data.swap(i, rng.below(i + 1));
+            }
+        }
+    }
+
+    /// The number of _data_ rows.
+    pub(crate) fn data_len(&self) -> usize {
+        let s = if self.header { 1 } else { 0 };
+        self.rows_len().saturating_sub(s)
+    }
+}
+
+/// The nearest whole number of `len` items in the fraction `frac`.
+fn frac_of(frac: f64, len: usize) -> usize {
+    assert!(
+        (0.0..=1.0).contains(&frac),
+        "fraction {} is outside 0.0..=1.0",
+        frac
+    );
+    (frac * len as f64).round() as usize
+}
+
+/// A small, seeded pseudo-random number generator (SplitMix64).
+///
+/// The sequence is fixed for a seed, so samples are reproducible across platforms and releases.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A uniform number in `0..n`, which must not be empty.
+    fn below(&mut self, n: usize) -> usize {
+        // Lemire's multiply and reject method, which is unbiased
+        let n = n as u64;
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let m = u128::from(self.next_u64()) * u128::from(n);
+            if m as u64 >= threshold {
+                return (m >> 64) as usize;
+            }
+        }
+    }
+
+    /// `n` distinct numbers from `0..len` in random order, or all if `n > len`.
+    fn choose(&mut self, len: usize, n: usize) -> Vec<usize> {
+        let n = min(n, len);
+        let mut v = (0..len).collect::<Vec<_>>();
+        // partial Fisher-Yates
+        for i in 0..n {
+            v.swap(i, i + self.below(len - i));
+        }
+        v.truncate(n);
+        v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table<&'static str> {
+        let mut t = Table::new();
+        t.add_row(vec![Obj("id"), Obj("group")].into_iter());
+        t.add_rows(
+            (0..1000)
+                .map(|i| vec![Num(i.into()), Obj(if i % 10 == 0 { "a" } else { "b" })].into_iter()),
+        );
+        t
+    }
+
+    fn ids(t: &Table<&'static str>) -> Vec<Entry<&'static str>> {
+        t.col(0).unwrap().skip(1).cloned().collect()
+    }
+
+    #[test]
+    fn rng() {
+        let mut rng = Rng::new(0);
+        let mut counts = [0; 6];
+        for _ in 0..6000 {
+            counts[rng.below(6)] += 1;
+        }
+        assert!(
+            counts.iter().all(|&c| (900..1100).contains(&c)),
+            "{:?}",
+            counts
+        );
+        assert_eq!(Rng::new(5).choose(10, 20).len(), 10);
+        assert_eq!(Rng::new(5).choose(0, 2), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn samples() {
+        let t = table();
+        let s = t.sample(50, 1);
+        assert_eq!(s.rows_len(), 51);
+        assert_eq!(s.row(0).unwrap().next(), Some(&Obj("id")));
+        let ids = ids(&s);
+        assert!(ids
+            .windows(2)
+            .all(|w| w[0].total_cmp(&w[1]) == Ordering::Less));
+        assert_eq!(s, t.sample(50, 1));
+
+        assert_eq!(t.sample(2000, 1), t);
+        assert_eq!(t.sample_frac(0.25, 9).rows_len(), 251);
+        assert_eq!(t.sample_frac(0.0, 9).rows_len(), 1);
+
+        let mut u = table();
+        u.set_header(false);
+        assert_eq!(u.sample(10, 1).rows_len(), 10);
+        assert_eq!(Table::<()>::new().sample(10, 1).rows_len(), 0);
+    }
+
+    #[test]
+    fn stratified() {
+        let t = table();
+        let s = t.sample_stratified(1, 0.1, 4);
+        let a = s.col(1).unwrap().filter(|e| **e == Obj("a")).count();
+        assert_eq!((s.rows_len(), a), (101, 10));
+        assert_eq!(s, t.sample_stratified(1, 0.1, 4));
+        assert_eq!(t.sample_stratified(1, 1.0, 4), t);
+    }
+
+    #[test]
+    fn splits() {
+        let t = table();
+        let (a, b) = t.split(0.7, 11);
+        assert_eq!((a.rows_len(), b.rows_len()), (701, 301));
+        assert_eq!(b.row(0).unwrap().next(), Some(&Obj("id")));
+
+        let mut all = ids(&a);
+        all.extend(ids(&b));
+        all.sort_by(Entry::total_cmp);
+        assert_eq!(all, ids(&t));
+        assert_eq!((a, b), t.split(0.7, 11));
+    }
+
+    #[test]
+    fn shuffles() {
+        let mut t = table();
+        t.shuffle_rows(8);
+        assert_eq!(t.row(0).unwrap().next(), Some(&Obj("id")));
+        let mut u = table();
+        u.shuffle_rows(8);
+        assert_eq!(t, u);
+
+        t.sort(0, Entry::total_cmp);
+        assert_eq!(t, table());
+    }
+
+    #[test]
+    #[should_panic]
+    fn frac_panic() {
+        table().sample_frac(1.5, 0);
+    }
+}
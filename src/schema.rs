@@ -0,0 +1,542 @@
+use super::*;
+use std::{cmp::Reverse, fmt};
+
+/// The kind of non-nil entry a column holds, see [`Schema`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EntryKind {
+    /// Any non-nil entry.
+    Any,
+    /// [`Entry::Bool`] entries.
+    Bool,
+    /// [`Entry::Num`] entries.
+    Num,
+    /// [`Entry::Date`] entries.
+    Date,
+    /// [`Entry::DateTime`] entries.
+    DateTime,
+    /// [`Entry::Duration`] entries.
+    Duration,
+    /// [`Entry::Obj`] entries.
+    Obj,
+}
+
+/// A column of a [`Schema`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Field {
+    /// The column header.
+    pub name: String,
+    /// The kind of non-nil entries.
+    pub kind: EntryKind,
+    /// The column may contain [`Entry::Nil`] entries.
+    pub nullable: bool,
+}
+
+/// The expected columns of a table.
+///
+/// If a table has a header row, fields are matched to columns by name, otherwise (or if the table
+/// has no rows) by position.
+/// Columns without a field are not checked.
+///
+/// ```rust
+/// # use table::*;
+/// use Entry::*;
+/// let mut r: Table<String> = Table::from(vec![
+///     vec![Obj("cut".into()), Obj("price".into())],
+///     vec![Obj("Ideal".into()), Num(326.into())],
+///     vec![Obj("Fair".into()), Obj("N/A".into())],
+///     vec![Nil, Obj("334".into())],
+/// ]);
+///
+/// let schema = Schema::new()
+///     .field("price", EntryKind::Num, true)
+///     .field("cut", EntryKind::Obj, false);
+///
+/// assert_eq!(r.validate(&schema), vec![
+///     Violation::Kind { row: 2, col: 1, expected: EntryKind::Num, found: EntryKind::Obj },
+///     Violation::Kind { row: 3, col: 1, expected: EntryKind::Num, found: EntryKind::Obj },
+///     Violation::Nil { row: 3, col: 0 },
+/// ]);
+///
+/// // "334" is converted, "N/A" is not
+/// let remaining = r.coerce(&schema);
+/// assert_eq!(remaining.len(), 2);
+/// assert_eq!(r[(3, 1)], Num(334.into()));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Schema {
+    fields: Vec<Field>,
+}
+
+/// A violation of a [`Schema`], see [`Table::validate`].
+///
+/// Rows and columns are table indices.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Violation {
+    /// No column matches the field with `name`.
+    MissingColumn {
+        /// The field name.
+        name: String,
+    },
+    /// A nil entry in a column which is not nullable.
+    Nil {
+        /// The row index.
+        row: usize,
+        /// The column index.
+        col: usize,
+    },
+    /// An entry of the wrong kind.
+    Kind {
+        /// The row index.
+        row: usize,
+        /// The column index.
+        col: usize,
+        /// The kind of the field.
+        expected: EntryKind,
+        /// The kind of the entry.
+        found: EntryKind,
+    },
+}
+
+impl<T> Entry<T> {
+    /// The kind of the entry, or `None` if the entry is [`Entry::Nil`].
+    pub fn kind(&self) -> Option<EntryKind> {
+        match self {
+            Nil => None,
+            Bool(_) => Some(EntryKind::Bool),
+            Num(_) => Some(EntryKind::Num),
+            Date(_) => Some(EntryKind::Date),
+            DateTime(_) => Some(EntryKind::DateTime),
+            Duration(_) => Some(EntryKind::Duration),
+            Obj(_) => Some(EntryKind::Obj),
+        }
+    }
+}
+
+impl EntryKind {
+    /// An entry of kind `found` is allowed by this kind.
+    pub fn allows(self, found: EntryKind) -> bool {
+        self == EntryKind::Any || self == found
+    }
+}
+
+impl Schema {
+    /// An empty schema.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a field.
+    pub fn field(mut self, name: &str, kind: EntryKind, nullable: bool) -> Self {
+        self.fields.push(Field {
+            name: name.to_string(),
+            kind,
+            nullable,
+        });
+        self
+    }
+
+    /// The fields, in order.
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    /// Retrieve the field with `name`.
+    pub fn get(&self, name: &str) -> Option<&Field> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+}
+
+impl<T: AsRef<str> + Sync> Table<T> {
+    /// Derive a schema from the _data_ entries.
+    ///
+    /// Each column has a field named by its header, or its index if the table has no header row
+    /// or no rows.
+    /// The kind is the most frequent kind of the non-nil entries, ties going to the first in
+    /// [`EntryKind`] order, so validating a table against its inferred schema reports the
+    /// minority entries of mixed columns. Columns of only nils are [`EntryKind::Any`]. A field
+    /// is nullable if the column contains a nil.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// let r = parse_dsv(',', "id,joined,active\n1,2020-01-31,true\n2,,false\nN/A,2020-02-01,true");
+    /// let schema = r.infer_schema();
+    /// assert_eq!(schema, Schema::new()
+    ///     .field("id", EntryKind::Num, false)
+    ///     .field("joined", EntryKind::Date, true)
+    ///     .field("active", EntryKind::Bool, false));
+    /// assert_eq!(r.validate(&schema).len(), 1);
+    /// ```
+    ///
+    /// # Parallelisation
+    /// Columns are inferred in parallel.
+    pub fn infer_schema(&self) -> Schema {
+        let s = if self.header { 1 } else { 0 };
+        let data = self.data.get(s..).unwrap_or_default();
+        let mut fields = Vec::with_capacity(self.cols_len());
+        fields.par_extend((0..self.cols_len()).into_par_iter().map(|c| {
+            // counts of each kind, in EntryKind order
+            let mut counts = [0usize; 7];
+            let mut nullable = false;
+            for row in data {
+                match row[c].kind() {
+                    Some(k) => counts[k as usize] += 1,
+                    None => nullable = true,
+                }
+            }
+            let kind = KINDS
+                .iter()
+                .zip(counts)
+                .filter(|x| x.1 > 0)
+                .max_by_key(|&(&k, n)| (n, Reverse(k)))
+                .map_or(EntryKind::Any, |x| *x.0);
+            let name = match self.data.first().filter(|_| self.header) {
+                Some(head) => head[c].as_str().into_owned(),
+                None => c.to_string(),
+            };
+            Field {
+                name,
+                kind,
+                nullable,
+            }
+        }));
+        Schema { fields }
+    }
+
+    /// Check the _data_ entries against `schema`, reporting every violation.
+    ///
+    /// Violations are grouped by field in schema order, with rows in ascending order. An empty
+    /// vector means the table is valid.
+    ///
+    /// # Parallelisation
+    /// Fields are checked in parallel.
+    pub fn validate(&self, schema: &Schema) -> Vec<Violation> {
+        let s = if self.header { 1 } else { 0 };
+        let data = self.data.get(s..).unwrap_or_default();
+        let mut v = Vec::new();
+        v.par_extend(
+            schema
+                .fields
+                .par_iter()
+                .enumerate()
+                .flat_map_iter(|(i, field)| {
+                    let col = self.schema_col(i, field);
+                    let missing = col.is_none().then(|| Violation::MissingColumn {
+                        name: field.name.clone(),
+                    });
+                    let cells = col.into_iter().flat_map(move |c| {
+                        data.iter()
+                            .enumerate()
+                            .filter_map(move |(r, row)| violation(field, &row[c], r + s, c))
+                    });
+                    missing.into_iter().chain(cells)
+                }),
+        );
+        v
+    }
+
+    /// Convert the _data_ entries to the kinds of `schema` where possible, returning the
+    /// violations which remain.
+    ///
+    /// | To | From |
+    /// | -- | ---- |
+    /// | `Bool` | `Num` 0 and 1, and objects parsed as by [`parse_dsv`]. |
+    /// | `Num` | `Bool`, and objects parsed as by [`parse_dsv`]. |
+    /// | `Date`, `Duration` | Objects parsed as by [`parse_dsv`]. |
+    /// | `DateTime` | `Date` at midnight, and objects parsed as by [`parse_dsv`]. |
+    /// | `Obj` | Any entry, using [`Entry::as_str`]. |
+    ///
+    /// Objects which are empty or only whitespace become [`Nil`] when coerced to any kind other
+    /// than `Obj`. Other entries are unchanged.
+    ///
+    /// # Parallelisation
+    /// Entries are converted in parallel by row.
+    pub fn coerce(&mut self, schema: &Schema) -> Vec<Violation>
+    where
+        T: Send + for<'s> From<&'s str>,
+    {
+        let cols = schema
+            .fields
+            .iter()
+            .enumerate()
+            .filter_map(|(i, f)| self.schema_col(i, f).map(|c| (c, f.kind)))
+            .collect::<Vec<_>>();
+        let s = if self.header { 1 } else { 0 };
+        if let Some(data) = self.data.get_mut(s..) {
+            data.par_iter_mut().for_each(|row| {
+                for &(c, kind) in &cols {
+                    if let Some(e) = coerce(&row[c], kind) {
+                        row[c] = e;
+                    }
+                }
+            });
+        }
+        self.validate(schema)
+    }
+
+    /// The column of the `i`th field.
+    fn schema_col(&self, i: usize, field: &Field) -> Option<usize> {
+        match self.header && !self.data.is_empty() {
+            true => self.col_by_name(&field.name),
+            false => Some(i).filter(|&i| i < self.cols_len()),
+        }
+    }
+}
+
+const KINDS: [EntryKind; 7] = [
+    EntryKind::Any,
+    EntryKind::Bool,
+    EntryKind::Num,
+    EntryKind::Date,
+    EntryKind::DateTime,
+    EntryKind::Duration,
+    EntryKind::Obj,
+];
+
+fn violation<T>(field: &Field, e: &Entry<T>, row: usize, col: usize) -> Option<Violation> {
+    match e.kind() {
+        None if !field.nullable => Some(Violation::Nil { row, col }),
+        Some(found) if !field.kind.allows(found) => Some(Violation::Kind {
+            row,
+            col,
+            expected: field.kind,
+            found,
+        }),
+        _ => None,
+    }
+}
+
+/// The entry `e` converted to `kind`, if it is not already and can be.
+fn coerce<T>(e: &Entry<T>, kind: EntryKind) -> Option<Entry<T>>
+where
+    T: AsRef<str> + for<'s> From<&'s str>,
+{
+    if kind.allows(e.kind()?) {
+        return None;
+    }
+    match (kind, e) {
+        (EntryKind::Obj, e) => Some(Obj(T::from(e.as_str().as_ref()))),
+        (EntryKind::Bool, Num(n)) if *n == Number::from(0) || *n == Number::from(1) => {
+            Some(Bool(*n == Number::from(1)))
+        }
+        (EntryKind::Num, Bool(b)) => Some(Num(u8::from(*b).into())),
+        (EntryKind::DateTime, Date(d)) => Some(DateTime(d.and_midnight())),
+        (kind, Obj(o)) => match parsing::map_entry(o.as_ref().trim()) {
+            Nil => Some(Nil),
+            Date(d) if kind == EntryKind::DateTime => Some(DateTime(d.and_midnight())),
+            e if e.kind() == Some(kind) => Some(e.map_obj(T::from)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::MissingColumn { name } => write!(f, "no column for field '{}'", name),
+            Violation::Nil { row, col } => {
+                write!(
+                    f,
+                    "nil entry at ({}, {}) in a non-nullable column",
+                    row, col
+                )
+            }
+            Violation::Kind {
+                row,
+                col,
+                expected,
+                found,
+            } => write!(
+                f,
+                "expected {:?} entry at ({}, {}), found {:?}",
+                expected, row, col, found
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table<String> {
+        let o = |s: &str| Obj(s.to_string());
+        Table::from(vec![
+            vec![o("id"), o("when"), o("ok"), o("note")],
+            vec![Num(1.into()), o("2020-01-31"), Bool(true), o("a")],
+            vec![
+                o(" 2 "),
+                o("2020-01-31T10:00:00Z"),
+                Num(0.into()),
+                Num(5.into()),
+            ],
+            vec![o("N/A"), o(""), o("TRUE"), Nil],
+            vec![Num(4.into()), Nil, Num(2.into()), o("d")],
+        ])
+    }
+
+    #[test]
+    fn inferring() {
+        let t = table();
+        let schema = t.infer_schema();
+        assert_eq!(
+            schema.fields(),
+            &[
+                Field {
+                    name: "id".into(),
+                    kind: EntryKind::Num,
+                    nullable: false
+                },
+                Field {
+                    name: "when".into(),
+                    kind: EntryKind::Obj,
+                    nullable: true
+                },
+                Field {
+                    name: "ok".into(),
+                    kind: EntryKind::Num,
+                    nullable: false
+                },
+                Field {
+                    name: "note".into(),
+                    kind: EntryKind::Obj,
+                    nullable: true
+                },
+            ]
+        );
+        // ties go to the first kind
+        assert_eq!(schema.get("id").unwrap().kind, EntryKind::Num);
+
+        let mut t = table();
+        t.set_header(false);
+        t.add_col(repeat_n(Nil, 5));
+        let schema = t.infer_schema();
+        assert_eq!(schema.fields()[0].name, "0");
+        assert_eq!(schema.fields()[0].kind, EntryKind::Obj);
+        assert_eq!(schema.fields()[4].kind, EntryKind::Any);
+        assert!(schema.fields()[4].nullable);
+        assert!(t.validate(&schema).len() > 1);
+        assert_eq!(Table::<String>::new().infer_schema(), Schema::new());
+
+        let mut t = Table::<String>::new();
+        t.add_col(empty::<Entry<String>>());
+        let schema = t.infer_schema();
+        assert_eq!(schema.fields()[0].name, "0");
+        assert_eq!(schema.fields()[0].kind, EntryKind::Any);
+        assert!(t.validate(&schema).is_empty());
+    }
+
+    #[test]
+    fn validating() {
+        let t = table();
+        let schema = Schema::new()
+            .field("ok", EntryKind::Bool, false)
+            .field("missing", EntryKind::Any, true)
+            .field("note", EntryKind::Any, false);
+        let v = t.validate(&schema);
+        assert_eq!(
+            v,
+            vec![
+                Violation::Kind {
+                    row: 2,
+                    col: 2,
+                    expected: EntryKind::Bool,
+                    found: EntryKind::Num
+                },
+                Violation::Kind {
+                    row: 3,
+                    col: 2,
+                    expected: EntryKind::Bool,
+                    found: EntryKind::Obj
+                },
+                Violation::Kind {
+                    row: 4,
+                    col: 2,
+                    expected: EntryKind::Bool,
+                    found: EntryKind::Num
+                },
+                Violation::MissingColumn {
+                    name: "missing".into()
+                },
+                Violation::Nil { row: 3, col: 3 },
+            ]
+        );
+        assert_eq!(v[1].to_string(), "expected Bool entry at (3, 2), found Obj");
+
+        // by position
+        let mut t = table();
+        t.set_header(false);
+        let schema = Schema::new()
+            .field("x", EntryKind::Any, false)
+            .field("y", EntryKind::Any, true)
+            .field("z", EntryKind::Any, true)
+            .field("w", EntryKind::Any, true)
+            .field("v", EntryKind::Any, true);
+        assert_eq!(
+            t.validate(&schema),
+            vec![Violation::MissingColumn { name: "v".into() }]
+        );
+    }
+
+    #[test]
+    fn coercing() {
+        let mut t = table();
+        let schema = Schema::new()
+            .field("id", EntryKind::Num, false)
+            .field("when", EntryKind::DateTime, true)
+            .field("ok", EntryKind::Bool, false)
+            .field("note", EntryKind::Obj, true);
+        let v = t.coerce(&schema);
+        assert_eq!(
+            v,
+            vec![
+                Violation::Kind {
+                    row: 3,
+                    col: 0,
+                    expected: EntryKind::Num,
+                    found: EntryKind::Obj
+                },
+                Violation::Kind {
+                    row: 4,
+                    col: 2,
+                    expected: EntryKind::Bool,
+                    found: EntryKind::Num
+                },
+            ]
+        );
+
+        let dt = |s: &str| DateTime(s.parse().unwrap());
+        let col = |t: &Table<String>, c| t.col(c).unwrap().skip(1).cloned().collect::<Vec<_>>();
+        assert_eq!(
+            col(&t, 0),
+            vec![
+                Num(1.into()),
+                Num(2.into()),
+                Obj("N/A".into()),
+                Num(4.into())
+            ]
+        );
+        assert_eq!(
+            col(&t, 1),
+            vec![
+                dt("2020-01-31T00:00:00Z"),
+                dt("2020-01-31T10:00:00Z"),
+                Nil,
+                Nil
+            ]
+        );
+        assert_eq!(
+            col(&t, 2),
+            vec![Bool(true), Bool(false), Bool(true), Num(2.into())]
+        );
+        assert_eq!(
+            col(&t, 3),
+            vec![Obj("a".into()), Obj("5".into()), Nil, Obj("d".into())]
+        );
+
+        // objects from numbers are reparsed
+        let schema = Schema::new().field("note", EntryKind::Num, true);
+        t.coerce(&schema);
+        assert_eq!(col(&t, 3)[1], Num(5.into()));
+    }
+}
@@ -0,0 +1,289 @@
+use super::*;
+
+/// The direction to sort a column in.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Ascending order, smallest first.
+    Asc,
+    /// Descending order, largest first.
+    Desc,
+}
+
+/// Where to place [`Entry::Nil`] entries when sorting, irrespective of [`Direction`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Nils {
+    /// Nils are placed before any other entries.
+    First,
+    /// Nils are placed after any other entries.
+    Last,
+}
+
+impl<T> Table<T> {
+    /// Sort _data_ rows by multiple columns, each with its own [`Direction`] and [`Nils`]
+    /// placement.
+    ///
+    /// Keys are compared in order, with later keys only used to break ties of earlier keys.
+    /// Non-nil entries are compared using [`Entry::total_cmp`], which orders `Num` entries before
+    /// `Obj` entries.
+    ///
+    /// [`Table::sort_by_keys`] is a _stable_ sort.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let mut r: Table<&str> = Table::new();
+    /// r.add_rows(
+    ///     vec![
+    ///         vec![Obj("cut"), Obj("price")].into_iter(),
+    ///         vec![Obj("Good"), Num(300.into())].into_iter(),
+    ///         vec![Obj("Fair"), Num(200.into())].into_iter(),
+    ///         vec![Obj("Good"), Num(400.into())].into_iter(),
+    ///     ].into_iter()
+    /// );
+    ///
+    /// r.sort_by_keys(&[(0, Direction::Asc, Nils::Last), (1, Direction::Desc, Nils::Last)]);
+    /// let prices: Vec<_> = r.col(1).unwrap().skip(1).collect();
+    /// assert_eq!(prices, vec![&Num(200.into()), &Num(400.into()), &Num(300.into())]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if any key column is outside the columns bounds. See [`Table::try_sort_by_keys`].
+    ///
+    /// # Parallelisation
+    /// `sort_by_keys` uses parallelisation to efficiently sort table.
+    pub fn sort_by_keys(&mut self, keys: &[(usize, Direction, Nils)])
+    where
+        T: Ord + Send,
+    {
+        self.sort_by_keys_with(keys, Entry::total_cmp)
+    }
+
+    /// Sort _data_ rows by multiple columns. See [`Table::sort_by_keys`].
+    ///
+    /// Returns an error if any key column is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// `try_sort_by_keys` uses parallelisation to efficiently sort table.
+    pub fn try_sort_by_keys(&mut self, keys: &[(usize, Direction, Nils)]) -> Result<(), TableError>
+    where
+        T: Ord + Send,
+    {
+        self.try_sort_by_keys_with(keys, Entry::total_cmp)
+    }
+
+    /// Sort _data_ rows by multiple columns, comparing non-nil entries with `ordering`.
+    ///
+    /// [`Nils`] placement is handled before `ordering` is invoked, and [`Direction::Desc`] reverses
+    /// the result of `ordering`. See [`Table::sort_by_keys`].
+    ///
+    /// # Panics
+    /// Panics if any key column is outside the columns bounds. See
+    /// [`Table::try_sort_by_keys_with`].
+    ///
+    /// # Parallelisation
+    /// `sort_by_keys_with` uses parallelisation to efficiently sort table.
+    pub fn sort_by_keys_with<F>(&mut self, keys: &[(usize, Direction, Nils)], ordering: F)
+    where
+        T: Send,
+        F: Fn(&Entry<T>, &Entry<T>) -> Ordering + Sync,
+    {
+        or_panic(self.try_sort_by_keys_with(keys, ordering))
+    }
+
+    /// Sort _data_ rows by multiple columns, comparing non-nil entries with `ordering`. See
+    /// [`Table::sort_by_keys_with`].
+    ///
+    /// Returns an error if any key column is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// `try_sort_by_keys_with` uses parallelisation to efficiently sort table.
+    pub fn try_sort_by_keys_with<F>(
+        &mut self,
+        keys: &[(usize, Direction, Nils)],
+        ordering: F,
+    ) -> Result<(), TableError>
+    where
+        T: Send,
+        F: Fn(&Entry<T>, &Entry<T>) -> Ordering + Sync,
+    {
+        for &(col, _, _) in keys {
+            TableError::check_col(col, self.cols_len())?;
+        }
+
+        let s = if self.header { 1 } else { 0 };
+        let data = self.data.get_mut(s..).unwrap_or_default();
+        data.par_sort_by(|a, b| {
+            keys.iter()
+                .map(|&(col, dir, nils)| cmp_key(&a[col], &b[col], dir, nils, &ordering))
+                .find(|x| x.is_ne())
+                .unwrap_or(Ordering::Equal)
+        });
+        Ok(())
+    }
+}
+
+pub(crate) fn cmp_key<T, F>(
+    a: &Entry<T>,
+    b: &Entry<T>,
+    dir: Direction,
+    nils: Nils,
+    ordering: F,
+) -> Ordering
+where
+    F: Fn(&Entry<T>, &Entry<T>) -> Ordering,
+{
+    let nil = match nils {
+        Nils::First => Ordering::Less,
+        Nils::Last => Ordering::Greater,
+    };
+
+    match (a, b) {
+        (Nil, Nil) => Ordering::Equal,
+        (Nil, _) => nil,
+        (_, Nil) => nil.reverse(),
+        (a, b) => match dir {
+            Direction::Asc => ordering(a, b),
+            Direction::Desc => ordering(a, b).reverse(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table<&'static str> {
+        Table::from(vec![
+            vec![Obj("cut"), Obj("price")],
+            vec![Obj("Ideal"), Num(326.into())],
+            vec![Nil, Num(500.into())],
+            vec![Obj("Fair"), Nil],
+            vec![Obj("Ideal"), Num(2757.into())],
+            vec![Obj("Fair"), Num(337.into())],
+            vec![Num(1.into()), Num(1.into())],
+        ])
+    }
+
+    #[test]
+    fn single_key() {
+        let mut t = table();
+        t.sort_by_keys(&[(0, Direction::Asc, Nils::First)]);
+        let col: Vec<_> = t.col(0).unwrap().cloned().collect();
+        assert_eq!(
+            col,
+            vec![
+                Obj("cut"),
+                Nil,
+                Num(1.into()),
+                Obj("Fair"),
+                Obj("Fair"),
+                Obj("Ideal"),
+                Obj("Ideal")
+            ]
+        );
+        // stable
+        assert_eq!(t.data[3][1], Nil);
+        assert_eq!(t.data[5][1], Num(326.into()));
+    }
+
+    #[test]
+    fn nils_ignore_direction() {
+        let mut t = table();
+        t.sort_by_keys(&[(0, Direction::Desc, Nils::Last)]);
+        let col: Vec<_> = t.col(0).unwrap().skip(1).cloned().collect();
+        assert_eq!(
+            col,
+            vec![
+                Obj("Ideal"),
+                Obj("Ideal"),
+                Obj("Fair"),
+                Obj("Fair"),
+                Num(1.into()),
+                Nil
+            ]
+        );
+    }
+
+    #[test]
+    fn multiple_keys() {
+        let mut t = table();
+        t.sort_by_keys(&[
+            (0, Direction::Asc, Nils::Last),
+            (1, Direction::Desc, Nils::First),
+        ]);
+        assert_eq!(
+            t.data,
+            vec![
+                vec![Obj("cut"), Obj("price")],
+                vec![Num(1.into()), Num(1.into())],
+                vec![Obj("Fair"), Nil],
+                vec![Obj("Fair"), Num(337.into())],
+                vec![Obj("Ideal"), Num(2757.into())],
+                vec![Obj("Ideal"), Num(326.into())],
+                vec![Nil, Num(500.into())],
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_variant_order() {
+        let mut t = table();
+        t.set_header(false);
+        let order = VariantOrder {
+            num: 7,
+            ..VariantOrder::default()
+        };
+        t.sort_by_keys_with(&[(0, Direction::Asc, Nils::Last)], |a, b| {
+            a.total_cmp_with(b, order)
+        });
+        let col: Vec<_> = t.col(0).unwrap().cloned().collect();
+        assert_eq!(
+            col,
+            vec![
+                Obj("Fair"),
+                Obj("Fair"),
+                Obj("Ideal"),
+                Obj("Ideal"),
+                Obj("cut"),
+                Num(1.into()),
+                Nil
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn key_out_of_bounds() {
+        table().sort_by_keys(&[
+            (0, Direction::Asc, Nils::Last),
+            (2, Direction::Asc, Nils::Last),
+        ]);
+    }
+
+    #[test]
+    fn key_out_of_bounds_err() {
+        let mut t = table();
+        let keys = [
+            (0, Direction::Asc, Nils::Last),
+            (2, Direction::Asc, Nils::Last),
+        ];
+        assert_eq!(
+            t.try_sort_by_keys(&keys),
+            Err(TableError::ColOutOfBounds { index: 2, cols: 2 })
+        );
+        assert_eq!(t, table());
+    }
+
+    #[test]
+    fn empty() {
+        let mut t = Table::<()>::new();
+        t.sort_by_keys(&[]);
+        assert!(t
+            .try_sort_by_keys(&[(0, Direction::Asc, Nils::Last)])
+            .is_err());
+
+        let mut t = table();
+        t.sort_by_keys(&[]);
+        assert_eq!(t, table());
+    }
+}
@@ -0,0 +1,251 @@
+use super::*;
+use std::{collections::HashMap, hash::Hash};
+
+/// The statistics reported by [`Table::describe`], in row order.
+const STATS: &[&str] = &[
+    "count", "nil", "mean", "std", "min", "25%", "50%", "75%", "max", "distinct", "top", "freq",
+];
+
+impl<T> Table<T> {
+    /// Describe each column with descriptive statistics, returning a new table.
+    ///
+    /// The returned table has a header row of the column headers (or the column index if the
+    /// table has no header row, or no rows), and a row for each statistic:
+    ///
+    /// | Statistic | Description |
+    /// | --------- | ----------- |
+    /// | `count` | Number of non-nil entries. |
+    /// | `nil` | Number of nil entries. |
+    /// | `mean`, `std` | Mean and sample standard deviation. _Numeric columns only_. |
+    /// | `min`, `25%`, `50%`, `75%`, `max` | Minimum, quartiles, and maximum. _Numeric columns only_. |
+    /// | `distinct` | Number of distinct non-nil entries. |
+    /// | `top`, `freq` | The most frequent entry and its frequency. _Object columns only_. |
+    ///
+    /// A column is numeric if all non-nil entries are `Num`, otherwise it is an object column.
+    /// Statistics which do not apply to a column are [`Nil`]. Quartiles use linear interpolation.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let mut r: Table<&str> = Table::new();
+    /// r.add_rows(
+    ///     vec![
+    ///         vec![Obj("cut"), Obj("price")].into_iter(),
+    ///         vec![Obj("Ideal"), Num(1.into())].into_iter(),
+    ///         vec![Obj("Fair"), Num(2.into())].into_iter(),
+    ///         vec![Obj("Ideal"), Nil].into_iter(),
+    ///     ].into_iter()
+    /// );
+    ///
+    /// let d = r.describe();
+    /// assert_eq!(d.row(0).unwrap().nth(2), Some(&Obj("price")));
+    /// assert_eq!(d.row(1).unwrap().nth(2), Some(&Num(2.into()))); // count
+    /// assert_eq!(d.row(3).unwrap().nth(2), Some(&Num(1.5.into()))); // mean
+    /// assert_eq!(d.row(11).unwrap().nth(1), Some(&Obj("Ideal"))); // top
+    /// ```
+    ///
+    /// # Parallelisation
+    /// `describe` calculates the statistics of each column in parallel.
+    pub fn describe(&self) -> Table<T>
+    where
+        T: Clone + Hash + Eq + Send + Sync + From<&'static str>,
+    {
+        let s = if self.header { 1 } else { 0 };
+        let data = self.data.get(s..).unwrap_or_default();
+
+        let mut cols: Vec<Vec<Entry<T>>> = Vec::with_capacity(self.cols_len() + 1);
+        cols.push(
+            once(Nil)
+                .chain(STATS.iter().map(|&x| Obj(T::from(x))))
+                .collect(),
+        );
+        cols.par_extend((0..self.cols_len()).into_par_iter().map(|c| {
+            let header = match self.data.first().filter(|_| self.header) {
+                Some(head) => head[c].clone(),
+                None => Num(c.into()),
+            };
+            describe_col(header, data.iter().map(|r| &r[c]))
+        }));
+
+        let mut table = Table::from(cols).transpose();
+        table.header = true;
+        table
+    }
+}
+
+fn describe_col<'a, T, I>(header: Entry<T>, col: I) -> Vec<Entry<T>>
+where
+    T: 'a + Clone + Hash + Eq,
+    I: Iterator<Item = &'a Entry<T>>,
+{
+    let mut nums = Vec::new();
+    let mut objs = 0;
+    let mut nils = 0;
+    // entry -> (frequency, first occurrence)
+    let mut freqs: HashMap<&Entry<T>, (usize, usize)> = HashMap::new();
+
+    for (i, e) in col.enumerate() {
+        match e {
+            Nil => nils += 1,
+            Num(n) => nums.push(*n),
+            _ => objs += 1,
+        }
+        if !e.is_nil() {
+            freqs.entry(e).or_insert((0, i)).0 += 1;
+        }
+    }
+
+    let n = |x: usize| Num(x.into());
+    let f = |x: f64| num::float(x).map_or(Nil, Num);
+
+    let mut numeric = vec![Nil; 7];
+    if objs == 0 && !nums.is_empty() {
+        nums.sort_unstable();
+        let xs = nums.iter().map(Number::as_f64).collect::<Vec<_>>();
+        let len = xs.len() as f64;
+        let mean = xs.iter().sum::<f64>() / len;
+        let var = xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (len - 1.0);
+        numeric = vec![
+            f(mean),
+            if xs.len() > 1 { f(var.sqrt()) } else { Nil },
+            Num(nums[0]),
+            f(quantile(&xs, 0.25)),
+            f(quantile(&xs, 0.5)),
+            f(quantile(&xs, 0.75)),
+            Num(nums[nums.len() - 1]),
+        ];
+    }
+
+    let (top, freq) = match objs {
+        0 => (Nil, Nil),
+        _ => freqs
+            .iter()
+            .max_by(|(_, (fa, ia)), (_, (fb, ib))| fa.cmp(fb).then(ib.cmp(ia)))
+            .map(|(e, (freq, _))| ((*e).clone(), n(*freq)))
+            .unwrap_or_default(),
+    };
+
+    let mut v = Vec::with_capacity(STATS.len() + 1);
+    v.push(header);
+    v.push(n(nums.len() + objs));
+    v.push(n(nils));
+    v.extend(numeric);
+    v.push(n(freqs.len()));
+    v.push(top);
+    v.push(freq);
+    v
+}
+
+/// Quantile `p` of _sorted_ `xs`, using linear interpolation.
+fn quantile(xs: &[f64], p: f64) -> f64 {
+    let pos = p * (xs.len() - 1) as f64;
+    let (lo, hi) = (pos.floor() as usize, pos.ceil() as usize);
+    xs[lo] + (xs[hi] - xs[lo]) * (pos - lo as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stat(t: &Table<String>, stat: &str, col: usize) -> Entry<String> {
+        let row = STATS.iter().position(|&x| x == stat).unwrap() + 1;
+        t.row(row).unwrap().nth(col + 1).unwrap().clone()
+    }
+
+    #[test]
+    fn numeric_column() {
+        let mut t = Table::<String>::new();
+        t.set_header(false);
+        t.add_col(
+            vec![
+                Num(1.into()),
+                Num(2.into()),
+                Nil,
+                Num(3.into()),
+                Num(4.into()),
+                Num(10.into()),
+            ]
+            .into_iter(),
+        );
+
+        let d = t.describe();
+        assert_eq!((d.rows_len(), d.cols_len()), (13, 2));
+        assert!(d.header);
+        assert_eq!(d.row(0).unwrap().nth(1), Some(&Num(0.into())));
+        assert_eq!(
+            d.col(0).unwrap().skip(1).cloned().collect::<Vec<_>>(),
+            STATS
+                .iter()
+                .map(|&x| Obj(x.to_string()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(stat(&d, "count", 0), Num(5.into()));
+        assert_eq!(stat(&d, "nil", 0), Num(1.into()));
+        assert_eq!(stat(&d, "mean", 0), Num(4.into()));
+        assert_eq!(stat(&d, "std", 0), Num(12.5f64.sqrt().into()));
+        assert_eq!(stat(&d, "min", 0), Num(1.into()));
+        assert_eq!(stat(&d, "25%", 0), Num(2.into()));
+        assert_eq!(stat(&d, "50%", 0), Num(3.into()));
+        assert_eq!(stat(&d, "75%", 0), Num(4.into()));
+        assert_eq!(stat(&d, "max", 0), Num(10.into()));
+        assert_eq!(stat(&d, "distinct", 0), Num(5.into()));
+        assert_eq!(stat(&d, "top", 0), Nil);
+        assert_eq!(stat(&d, "freq", 0), Nil);
+    }
+
+    #[test]
+    fn object_and_mixed_columns() {
+        let o = |s: &str| Obj(s.to_string());
+        let t = Table::from(vec![
+            vec![o("cut"), o("mixed"), o("empty")],
+            vec![o("Fair"), Num(1.into()), Nil],
+            vec![o("Ideal"), o("a"), Nil],
+            vec![o("Ideal"), Num(1.into()), Nil],
+            vec![o("Fair"), Nil, Nil],
+        ]);
+
+        let d = t.describe();
+        assert_eq!(
+            d.row(0).unwrap().cloned().collect::<Vec<_>>(),
+            vec![Nil, o("cut"), o("mixed"), o("empty")]
+        );
+        assert_eq!(stat(&d, "count", 0), Num(4.into()));
+        assert_eq!(stat(&d, "mean", 0), Nil);
+        assert_eq!(stat(&d, "distinct", 0), Num(2.into()));
+        // ties go to the first occurrence
+        assert_eq!(stat(&d, "top", 0), o("Fair"));
+        assert_eq!(stat(&d, "freq", 0), Num(2.into()));
+
+        assert_eq!(stat(&d, "count", 1), Num(3.into()));
+        assert_eq!(stat(&d, "nil", 1), Num(1.into()));
+        assert_eq!(stat(&d, "min", 1), Nil);
+        assert_eq!(stat(&d, "top", 1), Num(1.into()));
+
+        assert_eq!(stat(&d, "count", 2), Num(0.into()));
+        assert_eq!(stat(&d, "nil", 2), Num(4.into()));
+        assert_eq!(stat(&d, "mean", 2), Nil);
+        assert_eq!(stat(&d, "top", 2), Nil);
+    }
+
+    #[test]
+    fn no_rows() {
+        let mut t = Table::<String>::new();
+        t.add_col(empty::<Entry<String>>());
+        assert_eq!((t.rows_len(), t.cols_len()), (0, 1));
+
+        let d = t.describe();
+        assert_eq!(d.row(0).unwrap().nth(1), Some(&Num(0.into())));
+        assert_eq!(stat(&d, "count", 0), Num(0.into()));
+        assert_eq!(stat(&d, "mean", 0), Nil);
+    }
+
+    #[test]
+    fn quantiles() {
+        let xs = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(quantile(&xs, 0.0), 1.0);
+        assert_eq!(quantile(&xs, 0.25), 1.75);
+        assert_eq!(quantile(&xs, 0.5), 2.5);
+        assert_eq!(quantile(&xs, 1.0), 4.0);
+        assert_eq!(quantile(&[5.0], 0.75), 5.0);
+    }
+}
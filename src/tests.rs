@@ -377,6 +377,41 @@ fn remove_col_panic() {
     table.remove_col(0);
 }
 
+#[test]
+fn sort_headered_no_rows() {
+    let mut table = <Table<()>>::new();
+    table.add_col(empty::<Entry<()>>());
+    assert_eq!((table.rows_len(), table.cols_len()), (0, 1));
+    assert_eq!(table.try_sort(0, |_, _| Ordering::Equal), Ok(()));
+    table.sort(0, |_, _| Ordering::Equal);
+    table.reverse_rows();
+    assert_eq!(table.rows_len(), 0);
+}
+
+#[test]
+fn try_index_mutators() {
+    let mut table = Table::from(vec![vec![Obj(()), Nil], vec![Nil, Num(1.into())]]);
+    let rows = |index| Err(TableError::RowOutOfBounds { index, rows: 2 });
+    let cols = |index| Err(TableError::ColOutOfBounds { index, cols: 2 });
+
+    assert_eq!(table.try_insert_row(3, [Nil].iter()).map(|_| ()), rows(3));
+    assert_eq!(table.try_insert_col(3, [Nil].iter()).map(|_| ()), cols(3));
+    assert_eq!(table.try_remove_row(2).map(|_| ()), rows(2));
+    assert_eq!(table.try_remove_col(2).map(|_| ()), cols(2));
+    assert_eq!(table.try_remove_col_par(5).map(|_| ()), cols(5));
+    assert_eq!(table.try_sort(2, |_, _| Ordering::Equal), cols(2));
+    assert_eq!((table.rows_len(), table.cols_len()), (2, 2));
+
+    assert!(table.try_insert_row(2, [Nil].iter()).is_ok());
+    assert!(table.try_remove_col(0).is_ok());
+    assert_eq!(table.data, vec![vec![Nil], vec![Num(1.into())], vec![Nil]]);
+
+    assert_eq!(
+        TableError::ColOutOfBounds { index: 2, cols: 1 }.to_string(),
+        "index 2 is outside bounds of table columns 1"
+    );
+}
+
 #[test]
 fn entry_ordering() {
     use std::cmp::{Ordering::*, *};
@@ -489,3 +524,66 @@ fn test_cloning() {
     assert_eq!(table, table_2);
     assert_eq!(table_2, table_clone);
 }
+
+#[test]
+fn table_transpose() {
+    let table = Table::from(vec![
+        vec![Obj("a"), Obj("b"), Obj("c")],
+        vec![Num(1.into()), Num(2.into()), Num(3.into())],
+    ]);
+
+    let t = table.transpose();
+    assert_eq!((t.rows_len(), t.cols_len()), (3, 2));
+    assert_eq!(
+        t.data,
+        vec![
+            vec![Obj("a"), Num(1.into())],
+            vec![Obj("b"), Num(2.into())],
+            vec![Obj("c"), Num(3.into())]
+        ]
+    );
+    assert_eq!(t.transpose().data[0], vec![Obj("a"), Obj("b"), Obj("c")]);
+
+    let empty: Table<()> = Table::new();
+    assert_eq!(empty.transpose(), Table::new());
+}
+
+#[test]
+fn table_transpose_par() {
+    let vecs: Vec<Vec<Entry<()>>> = (0..57)
+        .map(|r| (0..131).map(|c| Num((r * 1000 + c).into())).collect())
+        .collect();
+
+    let a = Table::from(vecs.clone()).transpose();
+    let b = Table::from(vecs).transpose_par();
+    assert_eq!((b.rows_len(), b.cols_len()), (131, 57));
+    assert_eq!(a, b);
+}
+
+#[test]
+fn table_transpose_with_header() {
+    let mut table = Table::from(vec![
+        vec![Obj("metric"), Obj("name"), Obj("other")],
+        vec![Num(1.into()), Obj("a"), Nil],
+        vec![Num(2.into()), Obj("b"), Nil],
+    ]);
+    table.set_header(false);
+
+    let t = table.transpose_with_header(1);
+    assert!(t.header);
+    assert_eq!(
+        t.data,
+        vec![
+            vec![Obj("name"), Obj("a"), Obj("b")],
+            vec![Obj("metric"), Num(1.into()), Num(2.into())],
+            vec![Obj("other"), Nil, Nil]
+        ]
+    );
+}
+
+#[test]
+#[should_panic]
+fn transpose_with_header_panic() {
+    let table = Table::from(vec![vec![Obj(())]]);
+    table.transpose_with_header(1);
+}
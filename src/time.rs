@@ -0,0 +1,631 @@
+//! Calendar dates, UTC timestamps, and durations, as stored in [`Entry`](crate::Entry) cells.
+//!
+//! Each type parses from and formats as ISO-8601. Years are limited to `0000` to `9999`, the
+//! range representable without the expanded year format.
+use std::{error, fmt, str::FromStr};
+
+const SECS_PER_DAY: i64 = 86_400;
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+/// Days from `1970-01-01` to `0000-01-01`.
+const MIN_DAYS: i32 = -719_528;
+/// Days from `1970-01-01` to `9999-12-31`.
+const MAX_DAYS: i32 = 2_932_896;
+
+/// A calendar date, in the proleptic Gregorian calendar.
+///
+/// ```rust
+/// # use table::time::Date;
+/// let date: Date = "2020-01-31".parse().unwrap();
+/// assert_eq!(date.ymd(), (2020, 1, 31));
+/// assert_eq!(date.days(), 18292);
+/// assert_eq!(date.to_string(), "2020-01-31");
+/// assert!("2020-02-30".parse::<Date>().is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date(i32);
+
+/// An instant in time, in UTC with nanosecond precision.
+///
+/// Timestamps with an offset are converted to UTC when parsed, and timestamps without an offset
+/// are taken to be UTC. A space may separate the date and time.
+///
+/// ```rust
+/// # use table::time::DateTime;
+/// let dt: DateTime = "2020-01-31T10:00:00Z".parse().unwrap();
+/// assert_eq!(dt.timestamp(), 1_580_464_800);
+/// assert_eq!(dt.to_string(), "2020-01-31T10:00:00Z");
+///
+/// let dt: DateTime = "2020-01-31 20:30:00.25+10:30".parse().unwrap();
+/// assert_eq!(dt.to_string(), "2020-01-31T10:00:00.25Z");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime {
+    secs: i64,
+    nanos: u32,
+}
+
+/// A signed span of time, with nanosecond precision.
+///
+/// Formatted as an ISO-8601 duration using days, hours, minutes, and seconds. Years and months are
+/// not supported as they do not have a fixed length. A leading `-` denotes a negative duration.
+///
+/// ```rust
+/// # use table::time::Duration;
+/// let d: Duration = "P1DT2H3M4.5S".parse().unwrap();
+/// assert_eq!(d.as_secs_f64(), 93_784.5);
+/// assert_eq!(d.to_string(), "P1DT2H3M4.5S");
+/// assert_eq!("PT90M".parse::<Duration>().unwrap().to_string(), "PT1H30M");
+/// assert_eq!("-P2W".parse::<Duration>().unwrap(), Duration::from_secs(-14 * 86_400));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duration {
+    secs: i64,
+    /// Always in `0..NANOS_PER_SEC`, such that ordering is by `(secs, nanos)`.
+    nanos: u32,
+}
+
+/// An error parsing an ISO-8601 string.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ParseError(&'static str);
+
+impl Date {
+    /// The date of `year`, `month`, and `day`, where `month` and `day` start at 1.
+    ///
+    /// Returns `None` if the date does not exist or the year is outside `0..=9999`.
+    pub fn from_ymd(year: i32, month: u32, day: u32) -> Option<Self> {
+        if !(0..=9999).contains(&year) || !(1..=12).contains(&month) {
+            return None;
+        }
+        if day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Date(days_from_civil(year, month, day)))
+    }
+
+    /// The date `days` after `1970-01-01`.
+    ///
+    /// Returns `None` if the year is outside `0..=9999`.
+    pub fn from_days(days: i32) -> Option<Self> {
+        (MIN_DAYS..=MAX_DAYS).contains(&days).then_some(Date(days))
+    }
+
+    /// The number of days since `1970-01-01`.
+    pub fn days(self) -> i32 {
+        self.0
+    }
+
+    /// The year, month, and day.
+    pub fn ymd(self) -> (i32, u32, u32) {
+        civil_from_days(self.0)
+    }
+
+    /// The date at midnight UTC.
+    pub fn and_midnight(self) -> DateTime {
+        DateTime {
+            secs: i64::from(self.0) * SECS_PER_DAY,
+            nanos: 0,
+        }
+    }
+}
+
+impl DateTime {
+    /// The instant `secs` and `nanos` after `1970-01-01T00:00:00Z`.
+    ///
+    /// Returns `None` if `nanos` is not less than one second, or the year is outside `0..=9999`.
+    pub fn from_timestamp(secs: i64, nanos: u32) -> Option<Self> {
+        let days = secs.div_euclid(SECS_PER_DAY);
+        let valid =
+            nanos < NANOS_PER_SEC && (i64::from(MIN_DAYS)..=i64::from(MAX_DAYS)).contains(&days);
+        valid.then_some(DateTime { secs, nanos })
+    }
+
+    /// The whole seconds since `1970-01-01T00:00:00Z`.
+    pub fn timestamp(self) -> i64 {
+        self.secs
+    }
+
+    /// The nanoseconds past the whole second.
+    pub fn subsec_nanos(self) -> u32 {
+        self.nanos
+    }
+
+    /// The UTC date.
+    pub fn date(self) -> Date {
+        Date(self.secs.div_euclid(SECS_PER_DAY) as i32)
+    }
+
+    /// The UTC hour, minute, and second.
+    pub fn hms(self) -> (u32, u32, u32) {
+        let s = self.secs.rem_euclid(SECS_PER_DAY) as u32;
+        (s / 3600, s / 60 % 60, s % 60)
+    }
+
+    /// `self + d`, returning `None` if the year leaves `0..=9999`.
+    pub fn checked_add(self, d: Duration) -> Option<Self> {
+        let d = Duration::from_nanos(self.as_duration().as_nanos() + d.as_nanos())?;
+        DateTime::from_timestamp(d.secs, d.nanos)
+    }
+
+    /// `self - d`, returning `None` if the year leaves `0..=9999`.
+    pub fn checked_sub(self, d: Duration) -> Option<Self> {
+        self.checked_add(d.checked_neg()?)
+    }
+
+    /// The duration from `earlier` to `self`, negative if `earlier` is later.
+    pub fn duration_since(self, earlier: DateTime) -> Duration {
+        // cannot overflow given the range of years
+        self.as_duration()
+            .checked_sub(earlier.as_duration())
+            .expect("duration in range")
+    }
+
+    fn as_duration(self) -> Duration {
+        Duration {
+            secs: self.secs,
+            nanos: self.nanos,
+        }
+    }
+}
+
+impl Duration {
+    /// A duration of `secs` seconds.
+    pub fn from_secs(secs: i64) -> Self {
+        Duration { secs, nanos: 0 }
+    }
+
+    /// A duration of `secs` seconds and `nanos` nanoseconds, where `nanos` carries into `secs`.
+    ///
+    /// Returns `None` on overflow.
+    pub fn new(secs: i64, nanos: u32) -> Option<Self> {
+        let secs = secs.checked_add(i64::from(nanos / NANOS_PER_SEC))?;
+        Some(Duration {
+            secs,
+            nanos: nanos % NANOS_PER_SEC,
+        })
+    }
+
+    /// A duration of `secs` fractional seconds, rounded to the nanosecond.
+    ///
+    /// Returns `None` if `secs` is not finite or out of range.
+    pub fn from_secs_f64(secs: f64) -> Option<Self> {
+        let nanos = (secs * f64::from(NANOS_PER_SEC)).round();
+        if !nanos.is_finite() || nanos.abs() >= i128::MAX as f64 {
+            return None;
+        }
+        Duration::from_nanos(nanos as i128)
+    }
+
+    /// The duration in fractional seconds.
+    pub fn as_secs_f64(self) -> f64 {
+        self.secs as f64 + f64::from(self.nanos) / f64::from(NANOS_PER_SEC)
+    }
+
+    /// The whole seconds, rounded towards negative infinity.
+    pub fn secs(self) -> i64 {
+        self.secs
+    }
+
+    /// The nanoseconds past [`Duration::secs`], always positive.
+    pub fn subsec_nanos(self) -> u32 {
+        self.nanos
+    }
+
+    /// The duration is less than zero.
+    pub fn is_negative(self) -> bool {
+        self.secs < 0
+    }
+
+    /// `self + rhs`, returning `None` on overflow.
+    pub fn checked_add(self, rhs: Duration) -> Option<Self> {
+        Duration::from_nanos(self.as_nanos() + rhs.as_nanos())
+    }
+
+    /// `self - rhs`, returning `None` on overflow.
+    pub fn checked_sub(self, rhs: Duration) -> Option<Self> {
+        Duration::from_nanos(self.as_nanos() - rhs.as_nanos())
+    }
+
+    /// `-self`, returning `None` on overflow.
+    pub fn checked_neg(self) -> Option<Self> {
+        Duration::from_nanos(-self.as_nanos())
+    }
+
+    fn from_nanos(nanos: i128) -> Option<Self> {
+        let n = i128::from(NANOS_PER_SEC);
+        Some(Duration {
+            secs: i64::try_from(nanos.div_euclid(n)).ok()?,
+            nanos: nanos.rem_euclid(n) as u32,
+        })
+    }
+
+    fn as_nanos(self) -> i128 {
+        i128::from(self.secs) * i128::from(NANOS_PER_SEC) + i128::from(self.nanos)
+    }
+}
+
+impl FromStr for Date {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let err = ParseError("date");
+        match s.as_bytes() {
+            [y @ .., b'-', m1, m2, b'-', d1, d2] if y.len() == 4 => Date::from_ymd(
+                digits::<u32>(y).ok_or(err)? as i32,
+                digits::<u32>(&[*m1, *m2]).ok_or(err)?,
+                digits::<u32>(&[*d1, *d2]).ok_or(err)?,
+            )
+            .ok_or(err),
+            _ => Err(err),
+        }
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let err = ParseError("date-time");
+        if s.len() < 16 || !s.is_char_boundary(10) {
+            return Err(err);
+        }
+        let (date, rest) = s.split_at(10);
+        let date = date.parse::<Date>().map_err(|_| err)?;
+        let rest = match rest.as_bytes() {
+            [b'T' | b't' | b' ', rest @ ..] => rest,
+            _ => return Err(err),
+        };
+
+        // hh:mm[:ss[.fff]]
+        let (hour, min, rest) = match rest {
+            [h1, h2, b':', m1, m2, rest @ ..] => (
+                digits::<u32>(&[*h1, *h2]).filter(|&h| h < 24).ok_or(err)?,
+                digits::<u32>(&[*m1, *m2]).filter(|&m| m < 60).ok_or(err)?,
+                rest,
+            ),
+            _ => return Err(err),
+        };
+        let (sec, rest) = match rest {
+            [b':', s1, s2, rest @ ..] => (
+                digits::<u32>(&[*s1, *s2]).filter(|&s| s < 60).ok_or(err)?,
+                rest,
+            ),
+            _ => (0, rest),
+        };
+        let (nanos, rest) = match rest {
+            [b'.' | b',', rest @ ..] => {
+                let n = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+                if n == 0 || n > 9 {
+                    return Err(err);
+                }
+                let frac = digits::<u32>(&rest[..n]).ok_or(err)? * 10u32.pow(9 - n as u32);
+                (frac, &rest[n..])
+            }
+            _ => (0, rest),
+        };
+
+        // Z, ±hh, ±hhmm, ±hh:mm, or none
+        let offset = match rest {
+            [] | [b'Z' | b'z'] => 0,
+            [sign @ (b'+' | b'-'), h1, h2, rest @ ..] => {
+                let m = match rest {
+                    [] => 0,
+                    [m1, m2] | [b':', m1, m2] => digits::<u32>(&[*m1, *m2]).ok_or(err)?,
+                    _ => return Err(err),
+                };
+                let h = digits::<u32>(&[*h1, *h2]).ok_or(err)?;
+                if h > 23 || m > 59 {
+                    return Err(err);
+                }
+                let offset = i64::from(h * 3600 + m * 60);
+                if *sign == b'-' {
+                    -offset
+                } else {
+                    offset
+                }
+            }
+            _ => return Err(err),
+        };
+
+        let secs = date.and_midnight().secs + i64::from(hour * 3600 + min * 60 + sec) - offset;
+        DateTime::from_timestamp(secs, nanos).ok_or(err)
+    }
+}
+
+impl FromStr for Duration {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, ParseError> {
+        let err = ParseError("duration");
+        let (neg, s) = match s.strip_prefix('-') {
+            Some(s) => (true, s),
+            None => (false, s),
+        };
+        let mut s = s.strip_prefix('P').ok_or(err)?.as_bytes();
+
+        // designator -> seconds, in the order they must appear
+        const DATE: [(u8, i128); 2] = [
+            (b'W', 7 * SECS_PER_DAY as i128),
+            (b'D', SECS_PER_DAY as i128),
+        ];
+        const TIME: [(u8, i128); 3] = [(b'H', 3600), (b'M', 60), (b'S', 1)];
+
+        let nano = i128::from(NANOS_PER_SEC);
+        let mut nanos: i128 = 0;
+        let mut any = false;
+        let mut time = false;
+        let mut units = DATE.iter();
+        while !s.is_empty() {
+            if s[0] == b'T' && !time {
+                time = true;
+                units = TIME.iter();
+                s = &s[1..];
+                if s.is_empty() {
+                    return Err(err);
+                }
+                continue;
+            }
+
+            let n = s.iter().take_while(|b| b.is_ascii_digit()).count();
+            let (int, rest) = s.split_at(n);
+            let (frac, rest) = match rest {
+                [b'.' | b',', rest @ ..] if time => {
+                    let f = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+                    if f == 0 || f > 9 {
+                        return Err(err);
+                    }
+                    (Some(&rest[..f]), &rest[f..])
+                }
+                _ => (None, rest),
+            };
+            let (&unit, rest) = rest.split_first().ok_or(err)?;
+            let secs = units.find(|u| u.0 == unit).ok_or(err)?.1;
+            if n == 0 || n > 18 || (frac.is_some() && unit != b'S') {
+                return Err(err);
+            }
+
+            nanos += i128::from(digits::<u64>(int).ok_or(err)?) * secs * nano;
+            if let Some(f) = frac {
+                nanos += i128::from(digits::<u64>(f).ok_or(err)? * 10u64.pow(9 - f.len() as u32));
+            }
+            any = true;
+            s = rest;
+        }
+
+        if !any {
+            return Err(err);
+        }
+        Duration::from_nanos(if neg { -nanos } else { nanos }).ok_or(err)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (y, m, d) = self.ymd();
+        write!(f, "{:04}-{:02}-{:02}", y, m, d)
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (h, m, s) = self.hms();
+        write!(f, "{}T{:02}:{:02}:{:02}", self.date(), h, m, s)?;
+        write_frac(f, self.nanos)?;
+        write!(f, "Z")
+    }
+}
+
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let nanos = self.as_nanos();
+        if nanos < 0 {
+            write!(f, "-")?;
+        }
+        let nanos = nanos.unsigned_abs();
+        let n = u128::from(NANOS_PER_SEC);
+        let (secs, frac) = ((nanos / n) as u64, (nanos % n) as u32);
+        let day = SECS_PER_DAY as u64;
+        let (d, h, m, s) = (secs / day, secs % day / 3600, secs % 3600 / 60, secs % 60);
+
+        write!(f, "P")?;
+        if d > 0 {
+            write!(f, "{}D", d)?;
+        }
+        if h > 0 || m > 0 || s > 0 || frac > 0 || d == 0 {
+            write!(f, "T")?;
+        }
+        if h > 0 {
+            write!(f, "{}H", h)?;
+        }
+        if m > 0 {
+            write!(f, "{}M", m)?;
+        }
+        if s > 0 || frac > 0 || secs == 0 {
+            write!(f, "{}", s)?;
+            write_frac(f, frac)?;
+            write!(f, "S")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "invalid ISO-8601 {}", self.0)
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Write a fractional second, trimming trailing zeros, or nothing if `nanos` is zero.
+fn write_frac(f: &mut fmt::Formatter, nanos: u32) -> fmt::Result {
+    if nanos == 0 {
+        return Ok(());
+    }
+    let frac = format!("{:09}", nanos);
+    write!(f, ".{}", frac.trim_end_matches('0'))
+}
+
+/// Parse ascii digits, returning `None` if any byte is not a digit.
+fn digits<N: From<u8> + std::ops::Mul<Output = N> + std::ops::Add<Output = N>>(
+    bytes: &[u8],
+) -> Option<N> {
+    bytes.iter().try_fold(N::from(0), |acc, &b| {
+        b.is_ascii_digit()
+            .then(|| acc * N::from(10) + N::from(b - b'0'))
+    })
+}
+
+fn is_leap(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days since `1970-01-01` of a civil date, see <http://howardhinnant.github.io/date_algorithms.html>.
+fn days_from_civil(year: i32, month: u32, day: u32) -> i32 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400) as u32;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe as i32 - 719_468
+}
+
+/// The civil date of days since `1970-01-01`, the inverse of [`days_from_civil`].
+fn civil_from_days(days: i32) -> (i32, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097) as u32;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe as i32 + era * 400 + i32::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> Date {
+        s.parse().unwrap()
+    }
+
+    fn dt(s: &str) -> DateTime {
+        s.parse().unwrap()
+    }
+
+    fn dur(s: &str) -> Duration {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(date("1970-01-01").days(), 0);
+        assert_eq!(date("1969-12-31").days(), -1);
+        assert_eq!(date("0000-01-01").days(), MIN_DAYS);
+        assert_eq!(date("9999-12-31").days(), MAX_DAYS);
+        assert_eq!(date("2000-02-29").ymd(), (2000, 2, 29));
+        assert_eq!(Date::from_days(MAX_DAYS + 1), None);
+
+        for days in (MIN_DAYS..=MAX_DAYS).step_by(97) {
+            let d = Date::from_days(days).unwrap();
+            assert_eq!(date(&d.to_string()), d);
+        }
+
+        for s in [
+            "1900-02-29",
+            "2020-13-01",
+            "2020-00-10",
+            "2020-1-01",
+            "20200-01-01",
+            "２020-01-01",
+        ] {
+            assert_eq!(s.parse::<Date>(), Err(ParseError("date")), "{}", s);
+        }
+        assert!(date("2020-01-31") < date("2020-02-01"));
+    }
+
+    #[test]
+    fn datetimes() {
+        assert_eq!(dt("1970-01-01T00:00:00Z").timestamp(), 0);
+        assert_eq!(dt("1970-01-01T00:00Z"), dt("1970-01-01 00:00:00"));
+        assert_eq!(dt("2020-01-31T10:00:00+01:00"), dt("2020-01-31T09:00:00Z"));
+        assert_eq!(
+            dt("2020-01-31T00:30:00+0100").to_string(),
+            "2020-01-30T23:30:00Z"
+        );
+        assert_eq!(
+            dt("2020-01-31T23:30:00-02").to_string(),
+            "2020-02-01T01:30:00Z"
+        );
+        assert_eq!(dt("1969-12-31T23:59:59.5Z").timestamp(), -1);
+        assert_eq!(dt("1969-12-31T23:59:59.5Z").subsec_nanos(), 500_000_000);
+        assert_eq!(
+            dt("2020-01-31T10:00:00.000000001Z").to_string(),
+            "2020-01-31T10:00:00.000000001Z"
+        );
+        assert_eq!(dt("2020-01-31T10:11:12Z").hms(), (10, 11, 12));
+        assert_eq!(dt("2020-01-31T10:11:12Z").date(), date("2020-01-31"));
+        assert!(dt("2020-01-31T10:00:00Z") < dt("2020-01-31T10:00:00.5Z"));
+
+        let a = dt("2020-01-31T10:00:00Z");
+        let b = dt("2020-02-01T12:30:00.5Z");
+        assert_eq!(b.duration_since(a), dur("P1DT2H30M0.5S"));
+        assert_eq!(a.duration_since(b), dur("-P1DT2H30M0.5S"));
+        assert_eq!(a.checked_add(dur("P1DT2H30M0.5S")), Some(b));
+        assert_eq!(b.checked_sub(dur("P1DT2H30M0.5S")), Some(a));
+        assert_eq!(dt("9999-12-31T23:59:59Z").checked_add(dur("PT1S")), None);
+
+        for s in [
+            "2020-01-31",
+            "2020-01-31T24:00:00Z",
+            "2020-01-31T10:60:00Z",
+            "2020-01-31T10:00:00.Z",
+            "2020-01-31T10:00:00.1234567890Z",
+            "2020-01-31T10:00:00+1",
+            "2020-01-31X10:00:00Z",
+            "0000-01-01T00:00:00+01:00",
+        ] {
+            assert_eq!(s.parse::<DateTime>(), Err(ParseError("date-time")), "{}", s);
+        }
+    }
+
+    #[test]
+    fn durations() {
+        assert_eq!(dur("PT0S"), Duration::from_secs(0));
+        assert_eq!(dur("P0D").to_string(), "PT0S");
+        assert_eq!(dur("PT1.5S").as_secs_f64(), 1.5);
+        assert_eq!(dur("-PT1.5S").secs(), -2);
+        assert_eq!(dur("-PT1.5S").subsec_nanos(), 500_000_000);
+        assert_eq!(dur("-PT1.5S").to_string(), "-PT1.5S");
+        assert_eq!(dur("P1W2D").to_string(), "P9D");
+        assert_eq!(dur("P1DT1S").to_string(), "P1DT1S");
+        assert_eq!(dur("PT36H").to_string(), "P1DT12H");
+        assert_eq!(Duration::new(1, 2_500_000_000), Some(dur("PT3.5S")));
+        assert_eq!(Duration::from_secs_f64(-0.25), Some(dur("-PT0.25S")));
+        assert_eq!(Duration::from_secs_f64(f64::NAN), None);
+        assert!(dur("-PT1S").is_negative());
+        assert!(dur("-PT1S") < dur("PT0S"));
+        assert!(dur("PT1S") < dur("PT1.000000001S"));
+        assert_eq!(dur("PT1S").checked_sub(dur("PT1.5S")), Some(dur("-PT0.5S")));
+        assert_eq!(dur("PT1S").checked_add(dur("-PT1S")), Some(dur("PT0S")));
+        assert_eq!(Duration::from_secs(i64::MIN).checked_neg(), None);
+
+        for s in [
+            "P", "PT", "P1Y", "P1M", "PT1D", "P1H", "P1.5D", "PT1S1M", "P1D1W", "1D", "PTS",
+        ] {
+            assert_eq!(s.parse::<Duration>(), Err(ParseError("duration")), "{}", s);
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use super::*;
+use crate::sort::cmp_key;
+
+impl<T: Clone + Send + Sync> Table<T> {
+    /// The first `n` _data_ rows, or all if there are fewer. The header row is retained.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let r: Table<&str> = Table::from(vec![
+    ///     vec![Obj("price")],
+    ///     vec![Num(326.into())],
+    ///     vec![Num(334.into())],
+    ///     vec![Num(327.into())],
+    /// ]);
+    ///
+    /// assert_eq!(r.head(2).into_raw(), vec![
+    ///     vec![Obj("price")],
+    ///     vec![Num(326.into())],
+    ///     vec![Num(334.into())],
+    /// ]);
+    /// assert_eq!(r.tail(1).into_raw(), vec![vec![Obj("price")], vec![Num(327.into())]]);
+    /// ```
+    ///
+    /// # Parallelisation
+    /// Rows are cloned in parallel.
+    pub fn head(&self, n: usize) -> Table<T> {
+        let rows = (0..min(n, self.data_len())).collect::<Vec<_>>();
+        self.select_data_rows(&rows)
+    }
+
+    /// The last `n` _data_ rows, or all if there are fewer. The header row is retained.
+    ///
+    /// # Parallelisation
+    /// Rows are cloned in parallel.
+    pub fn tail(&self, n: usize) -> Table<T> {
+        let len = self.data_len();
+        let rows = (len.saturating_sub(n)..len).collect::<Vec<_>>();
+        self.select_data_rows(&rows)
+    }
+
+    /// The `n` _data_ rows with the largest entries in column `col`, largest first.
+    ///
+    /// [`Nil`] entries are placed according to `nils`, so [`Nils::Last`] only selects rows with
+    /// `Nil` entries if there are fewer than `n` others, and [`Nils::First`] selects them before
+    /// any others. Non-nil entries are compared using [`Entry::total_cmp`], and ties are kept in
+    /// row order. The header row is retained.
+    ///
+    /// Rows are selected without sorting the table, only the `n` selected rows are sorted.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let r: Table<&str> = Table::from(vec![
+    ///     vec![Obj("carat"), Obj("price")],
+    ///     vec![Num(0.23.into()), Num(326.into())],
+    ///     vec![Num(0.29.into()), Nil],
+    ///     vec![Num(0.31.into()), Num(335.into())],
+    ///     vec![Num(0.24.into()), Num(336.into())],
+    /// ]);
+    ///
+    /// let top = r.nlargest(2, 1, Nils::Last);
+    /// assert_eq!(top.col(0).unwrap().cloned().collect::<Vec<_>>(), vec![
+    ///     Obj("carat"),
+    ///     Num(0.24.into()),
+    ///     Num(0.31.into()),
+    /// ]);
+    ///
+    /// let top = r.nsmallest(2, 1, Nils::First);
+    /// assert_eq!(top[(1, 1)], Nil);
+    /// assert_eq!(top[(2, 1)], Num(326.into()));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// Candidate rows are selected in parallel chunks.
+    pub fn nlargest(&self, n: usize, col: usize, nils: Nils) -> Table<T>
+    where
+        T: Ord,
+    {
+        self.top(n, col, Direction::Desc, nils)
+    }
+
+    /// The `n` _data_ rows with the smallest entries in column `col`, smallest first.
+    ///
+    /// See [`Table::nlargest`].
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// Candidate rows are selected in parallel chunks.
+    pub fn nsmallest(&self, n: usize, col: usize, nils: Nils) -> Table<T>
+    where
+        T: Ord,
+    {
+        self.top(n, col, Direction::Asc, nils)
+    }
+
+    /// The first `n` _data_ rows when ordered by column `col`.
+    fn top(&self, n: usize, col: usize, dir: Direction, nils: Nils) -> Table<T>
+    where
+        T: Ord,
+    {
+        or_panic(TableError::check_col(col, self.cols_len()));
+        let s = if self.header { 1 } else { 0 };
+        let data = self.data.get(s..).unwrap_or_default();
+        let n = min(n, data.len());
+        if n == 0 {
+            return self.select_data_rows(&[]);
+        }
+
+        // ties are broken by row index, so the selection is deterministic
+        let cmp = |a: &usize, b: &usize| {
+            cmp_key(&data[*a][col], &data[*b][col], dir, nils, Entry::total_cmp).then(a.cmp(b))
+        };
+        let select = |rows: &mut [usize]| {
+            if rows.len() > n {
+                rows.select_nth_unstable_by(n, cmp);
+            }
+            rows[..min(n, rows.len())].to_vec()
+        };
+
+        // each chunk contributes its own first n rows as candidates
+        let chunk = max(n, data.len() / rayon::current_num_threads() + 1);
+        let mut all = (0..data.len()).collect::<Vec<_>>();
+        let mut candidates = all
+            .par_chunks_mut(chunk)
+            .flat_map_iter(select)
+            .collect::<Vec<_>>();
+        let mut rows = select(&mut candidates);
+        rows.par_sort_unstable_by(cmp);
+
+        self.select_data_rows(&rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n(x: i32) -> Entry<&'static str> {
+        Num(x.into())
+    }
+
+    fn table() -> Table<&'static str> {
+        Table::from(vec![
+            vec![Obj("id"), Obj("score")],
+            vec![n(0), n(5)],
+            vec![n(1), Nil],
+            vec![n(2), n(9)],
+            vec![n(3), n(5)],
+            vec![n(4), n(1)],
+            vec![n(5), Nil],
+        ])
+    }
+
+    fn ids(t: &Table<&'static str>) -> Vec<Entry<&'static str>> {
+        t.col(0).unwrap().skip(1).cloned().collect()
+    }
+
+    #[test]
+    fn head_tail() {
+        let t = table();
+        assert_eq!(ids(&t.head(2)), vec![n(0), n(1)]);
+        assert_eq!(ids(&t.tail(2)), vec![n(4), n(5)]);
+        assert_eq!(t.head(10), t);
+        assert_eq!(t.tail(10), t);
+        assert_eq!(t.head(0).into_raw(), vec![vec![Obj("id"), Obj("score")]]);
+
+        let mut u = table();
+        u.set_header(false);
+        assert_eq!(ids(&u.head(1)), Vec::<Entry<_>>::new());
+        assert_eq!(u.head(1)[(0, 1)], Obj("score"));
+        assert_eq!(Table::<()>::new().tail(3).rows_len(), 0);
+    }
+
+    #[test]
+    fn largest_smallest() {
+        let t = table();
+        assert_eq!(ids(&t.nlargest(3, 1, Nils::Last)), vec![n(2), n(0), n(3)]);
+        assert_eq!(ids(&t.nlargest(3, 1, Nils::First)), vec![n(1), n(5), n(2)]);
+        assert_eq!(ids(&t.nsmallest(2, 1, Nils::Last)), vec![n(4), n(0)]);
+        assert_eq!(
+            ids(&t.nsmallest(10, 1, Nils::Last)),
+            vec![n(4), n(0), n(3), n(2), n(1), n(5)]
+        );
+        assert_eq!(t.nlargest(2, 1, Nils::Last)[(0, 0)], Obj("id"));
+        assert_eq!(t.nlargest(0, 1, Nils::Last).rows_len(), 1);
+    }
+
+    #[test]
+    fn large() {
+        let mut t = Table::<()>::new();
+        t.set_header(false);
+        t.add_rows((0..10_000).map(|i| once(Num(((i * 7919) % 10_000).into()))));
+        let top = t.nlargest(5, 0, Nils::Last);
+        let expected = (9995..10_000)
+            .rev()
+            .map(|i| vec![Num(i.into())])
+            .collect::<Vec<_>>();
+        assert_eq!(top.into_raw(), expected);
+        let bottom = t.nsmallest(100, 0, Nils::Last);
+        assert!(bottom
+            .col(0)
+            .unwrap()
+            .eq((0..100).map(|i| Num(i.into())).collect::<Vec<_>>().iter()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn top_panic() {
+        table().nlargest(1, 2, Nils::Last);
+    }
+}
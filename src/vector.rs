@@ -0,0 +1,446 @@
+use super::*;
+
+/// A normalisation of a numeric column, see [`Table::normalise_col`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Norm {
+    /// Subtract the mean and divide by the sample standard deviation.
+    ZScore,
+    /// Rescale linearly to the range `0..=1`, such that the minimum is 0 and the maximum is 1.
+    MinMax,
+}
+
+impl<T: Send + Sync> Table<T> {
+    /// The sum of the `Num` _data_ entries in column `col`.
+    ///
+    /// Other entries are skipped. Returns `None` if there are no `Num` entries, or the sum is not
+    /// finite.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let r: Table<&str> = Table::from(vec![
+    ///     vec![Obj("price")],
+    ///     vec![Num(3.into())],
+    ///     vec![Nil],
+    ///     vec![Num(5.into())],
+    ///     vec![Num(1.into())],
+    /// ]);
+    ///
+    /// assert_eq!(r.col_sum(0), Some(9.into()));
+    /// assert_eq!(r.col_mean(0), Some(3.0));
+    /// assert_eq!(r.col_min(0), Some(1.into()));
+    /// assert_eq!(r.col_max(0), Some(5.into()));
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// The column is summed in parallel.
+    pub fn col_sum(&self, col: usize) -> Option<Number> {
+        or_panic(self.nums(col))
+            .map(Some)
+            .reduce_with(|a, b| num::add(a?, b?))
+            .flatten()
+    }
+
+    /// The mean of the `Num` _data_ entries in column `col`.
+    ///
+    /// Other entries are skipped. Returns `None` if there are no `Num` entries.
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// The column is summed in parallel.
+    pub fn col_mean(&self, col: usize) -> Option<f64> {
+        mean(or_panic(self.nums(col)))
+    }
+
+    /// The minimum of the `Num` _data_ entries in column `col`.
+    ///
+    /// Other entries are skipped. Returns `None` if there are no `Num` entries.
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// The column is searched in parallel.
+    pub fn col_min(&self, col: usize) -> Option<Number> {
+        or_panic(self.nums(col)).min()
+    }
+
+    /// The maximum of the `Num` _data_ entries in column `col`.
+    ///
+    /// Other entries are skipped. Returns `None` if there are no `Num` entries.
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// The column is searched in parallel.
+    pub fn col_max(&self, col: usize) -> Option<Number> {
+        or_panic(self.nums(col)).max()
+    }
+
+    /// Apply `f` to each `Num` _data_ entry in column `col`.
+    ///
+    /// Other entries are unchanged. Results which are not finite become [`Nil`].
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let mut r: Table<&str> = Table::from(vec![
+    ///     vec![Obj("x")],
+    ///     vec![Num(4.into())],
+    ///     vec![Nil],
+    ///     vec![Num(9.into())],
+    /// ]);
+    ///
+    /// r.col_apply(0, |n| n.as_f64().sqrt().into());
+    /// assert_eq!(r.col(0).unwrap().skip(1).cloned().collect::<Vec<_>>(), vec![Num(2.into()), Nil, Num(3.into())]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds. See [`Table::try_col_apply`].
+    ///
+    /// # Parallelisation
+    /// Entries are mapped in parallel.
+    pub fn col_apply<F>(&mut self, col: usize, f: F) -> &mut Self
+    where
+        F: Fn(Number) -> Number + Sync,
+    {
+        or_panic(self.try_col_apply(col, f))
+    }
+
+    /// Apply `f` to each `Num` _data_ entry in column `col`. See [`Table::col_apply`].
+    ///
+    /// Returns an error if `col` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// Entries are mapped in parallel.
+    pub fn try_col_apply<F>(&mut self, col: usize, f: F) -> Result<&mut Self, TableError>
+    where
+        F: Fn(Number) -> Number + Sync,
+    {
+        self.map_nums(col, |n| {
+            let n = f(n);
+            match n.as_f64().is_finite() {
+                true => Num(n),
+                false => Nil,
+            }
+        })
+    }
+
+    /// Multiply each `Num` _data_ entry in column `col` by `factor`.
+    ///
+    /// Other entries are unchanged. Integers remain integers where the product does not overflow.
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds. See [`Table::try_scale_col`].
+    ///
+    /// # Parallelisation
+    /// Entries are mapped in parallel.
+    pub fn scale_col(&mut self, col: usize, factor: Number) -> &mut Self {
+        or_panic(self.try_scale_col(col, factor))
+    }
+
+    /// Multiply each `Num` _data_ entry in column `col` by `factor`. See [`Table::scale_col`].
+    ///
+    /// Returns an error if `col` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// Entries are mapped in parallel.
+    pub fn try_scale_col(&mut self, col: usize, factor: Number) -> Result<&mut Self, TableError> {
+        self.map_nums(col, |n| num::mul(n, factor).map_or(Nil, Num))
+    }
+
+    /// Normalise the `Num` _data_ entries in column `col`.
+    ///
+    /// Other entries are unchanged and excluded from the statistics. If the spread of the column
+    /// is zero, or a z-score has fewer than two entries, the numeric entries become [`Nil`].
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let mut r: Table<&str> = Table::from(vec![
+    ///     vec![Obj("a"), Obj("b")],
+    ///     vec![Num(1.into()), Num(1.into())],
+    ///     vec![Nil, Num(2.into())],
+    ///     vec![Num(5.into()), Num(3.into())],
+    /// ]);
+    ///
+    /// r.normalise_col(0, Norm::MinMax).normalise_col(1, Norm::ZScore);
+    /// assert_eq!(
+    ///     r.col(0).unwrap().skip(1).cloned().collect::<Vec<_>>(),
+    ///     vec![Num(0.into()), Nil, Num(1.into())]
+    /// );
+    /// assert_eq!(
+    ///     r.col(1).unwrap().skip(1).cloned().collect::<Vec<_>>(),
+    ///     vec![Num((-1).into()), Num(0.into()), Num(1.into())]
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `col` is outside the columns bounds. See [`Table::try_normalise_col`].
+    ///
+    /// # Parallelisation
+    /// The statistics are calculated, and entries mapped, in parallel.
+    pub fn normalise_col(&mut self, col: usize, norm: Norm) -> &mut Self {
+        or_panic(self.try_normalise_col(col, norm))
+    }
+
+    /// Normalise the `Num` _data_ entries in column `col`. See [`Table::normalise_col`].
+    ///
+    /// Returns an error if `col` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// The statistics are calculated, and entries mapped, in parallel.
+    pub fn try_normalise_col(&mut self, col: usize, norm: Norm) -> Result<&mut Self, TableError> {
+        let (offset, spread) = match norm {
+            Norm::ZScore => {
+                let mean = mean(self.nums(col)?).unwrap_or_default();
+                let (sq, n) = self
+                    .nums(col)?
+                    .map(|x| ((x.as_f64() - mean).powi(2), 1usize))
+                    .reduce(|| (0.0, 0), |a, b| (a.0 + b.0, a.1 + b.1));
+                let std = match n {
+                    0 | 1 => 0.0,
+                    n => (sq / (n - 1) as f64).sqrt(),
+                };
+                (mean, std)
+            }
+            Norm::MinMax => {
+                let min = self.nums(col)?.min().map_or(0.0, |x| x.as_f64());
+                let max = self.nums(col)?.max().map_or(0.0, |x| x.as_f64());
+                (min, max - min)
+            }
+        };
+
+        self.map_nums(col, |x| {
+            let x = (x.as_f64() - offset) / spread;
+            num::float(x).map_or(Nil, Num)
+        })
+    }
+
+    /// Combine columns `a` and `b` entry by entry with `f`, adding the results as a new column.
+    ///
+    /// If the table has a header row, `name` is used as the header of the new column. The
+    /// [`Entry`] operators can be used directly, which propagate [`Nil`].
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let mut r: Table<&str> = Table::from(vec![
+    ///     vec![Obj("price"), Obj("qty")],
+    ///     vec![Num(3.into()), Num(2.into())],
+    ///     vec![Num(5.into()), Nil],
+    /// ]);
+    ///
+    /// r.zip_cols("total", 0, 1, |a, b| a * b);
+    /// assert_eq!(r.col(2).unwrap().cloned().collect::<Vec<_>>(), vec![Obj("total"), Num(6.into()), Nil]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `a` or `b` is outside the columns bounds. See [`Table::try_zip_cols`].
+    ///
+    /// # Parallelisation
+    /// Entries are combined in parallel.
+    pub fn zip_cols<'n, F>(&mut self, name: &'n str, a: usize, b: usize, f: F) -> &mut Self
+    where
+        T: From<&'n str>,
+        F: Fn(&Entry<T>, &Entry<T>) -> Entry<T> + Sync,
+    {
+        or_panic(self.try_zip_cols(name, a, b, f))
+    }
+
+    /// Combine columns `a` and `b` entry by entry with `f`, adding the results as a new column.
+    /// See [`Table::zip_cols`].
+    ///
+    /// Returns an error if `a` or `b` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// Entries are combined in parallel.
+    pub fn try_zip_cols<'n, F>(
+        &mut self,
+        name: &'n str,
+        a: usize,
+        b: usize,
+        f: F,
+    ) -> Result<&mut Self, TableError>
+    where
+        T: From<&'n str>,
+        F: Fn(&Entry<T>, &Entry<T>) -> Entry<T> + Sync,
+    {
+        TableError::check_col(a, self.cols_len())?;
+        TableError::check_col(b, self.cols_len())?;
+        let s = if self.header { 1 } else { 0 };
+
+        let mut new = Vec::with_capacity(self.rows_len());
+        if self.header {
+            new.push(Obj(T::from(name)));
+        }
+        new.par_extend(
+            self.data
+                .get(s..)
+                .unwrap_or_default()
+                .par_iter()
+                .map(|row| f(&row[a], &row[b])),
+        );
+
+        Ok(self.add_col(new.into_iter()))
+    }
+
+    /// The `Num` _data_ entries of column `col`, in parallel.
+    fn nums(&self, col: usize) -> Result<impl ParallelIterator<Item = Number> + '_, TableError> {
+        TableError::check_col(col, self.cols_len())?;
+        let s = if self.header { 1 } else { 0 };
+        Ok(self
+            .data
+            .get(s..)
+            .unwrap_or_default()
+            .par_iter()
+            .filter_map(move |row| row[col].num()))
+    }
+
+    /// Replace the `Num` _data_ entries of column `col` with `f`, in parallel.
+    fn map_nums<F>(&mut self, col: usize, f: F) -> Result<&mut Self, TableError>
+    where
+        F: Fn(Number) -> Entry<T> + Sync,
+    {
+        TableError::check_col(col, self.cols_len())?;
+        let s = if self.header { 1 } else { 0 };
+        if let Some(data) = self.data.get_mut(s..) {
+            data.par_iter_mut().for_each(|row| {
+                if let Num(n) = row[col] {
+                    row[col] = f(n);
+                }
+            });
+        }
+        Ok(self)
+    }
+}
+
+fn mean<I: ParallelIterator<Item = Number>>(nums: I) -> Option<f64> {
+    let (sum, n) = nums
+        .map(|x| (x.as_f64(), 1usize))
+        .reduce(|| (0.0, 0), |a, b| (a.0 + b.0, a.1 + b.1));
+    (n > 0).then(|| sum / n as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n<N: Into<Number>>(x: N) -> Entry<&'static str> {
+        Num(x.into())
+    }
+
+    fn table() -> Table<&'static str> {
+        Table::from(vec![
+            vec![Obj("a"), Obj("b"), Obj("c")],
+            vec![n(1), n(2.5), Obj("x")],
+            vec![n(2), Nil, Nil],
+            vec![n(3), n(-1), Obj("y")],
+            vec![Nil, n(4), n(1)],
+        ])
+    }
+
+    fn col(t: &Table<&'static str>, c: usize) -> Vec<Entry<&'static str>> {
+        t.col(c).unwrap().skip(1).cloned().collect()
+    }
+
+    #[test]
+    fn aggregates() {
+        let t = table();
+        assert_eq!(t.col_sum(0), Some(6.into()));
+        assert_eq!(t.col_sum(1), Some(5.5.into()));
+        assert_eq!(t.col_sum(2), Some(1.into()));
+        assert_eq!(t.col_mean(0), Some(2.0));
+        assert_eq!(t.col_mean(1), Some(5.5 / 3.0));
+        assert_eq!(t.col_min(1), Some((-1).into()));
+        assert_eq!(t.col_max(1), Some(4.into()));
+
+        let mut t = table();
+        t.set_header(false);
+        assert_eq!(t.col_sum(2), Some(1.into()));
+        t.add_row(vec![n(f64::MAX), n(f64::MAX), Nil].into_iter());
+        t.add_row(vec![n(f64::MAX), n(f64::MAX), Nil].into_iter());
+        assert_eq!(t.col_sum(0), None);
+
+        let t = Table::from(vec![vec![Obj("a")], vec![Nil], vec![Obj("b")]]);
+        assert_eq!(t.col_sum(0), None);
+        assert_eq!(t.col_mean(0), None);
+        assert_eq!(t.col_min(0), None);
+        assert_eq!(t.col_max(0), None);
+    }
+
+    #[test]
+    fn mapping() {
+        let mut t = table();
+        t.col_apply(1, |x| (x.as_f64() * 2.0).into())
+            .scale_col(0, 3.into())
+            .col_apply(2, |x| (x.as_f64() / 0.0).into());
+        assert_eq!(col(&t, 0), vec![n(3), n(6), n(9), Nil]);
+        assert_eq!(col(&t, 1), vec![n(5), Nil, n(-2), n(8)]);
+        assert_eq!(col(&t, 2), vec![Obj("x"), Nil, Obj("y"), Nil]);
+        assert_eq!(t.row(0).unwrap().next(), Some(&Obj("a")));
+    }
+
+    #[test]
+    fn normalising() {
+        let mut t = table();
+        t.normalise_col(0, Norm::ZScore)
+            .normalise_col(1, Norm::MinMax);
+        assert_eq!(col(&t, 0), vec![n(-1), n(0), n(1), Nil]);
+        assert_eq!(col(&t, 1), vec![n(0.7), Nil, n(0), n(1)]);
+
+        // zero spread
+        let mut t = table();
+        t.normalise_col(2, Norm::ZScore);
+        assert_eq!(col(&t, 2), vec![Obj("x"), Nil, Obj("y"), Nil]);
+        let mut t = table();
+        t.add_col(vec![Obj("d"), n(2), n(2), Nil, n(2)].into_iter());
+        t.normalise_col(3, Norm::MinMax);
+        assert_eq!(col(&t, 3), vec![Nil; 4]);
+    }
+
+    #[test]
+    fn zipping() {
+        let mut t = table();
+        t.zip_cols("sum", 0, 1, |a, b| a + b);
+        assert_eq!(t.cols_len(), 4);
+        assert_eq!(
+            t.col(3).unwrap().cloned().collect::<Vec<_>>(),
+            vec![Obj("sum"), n(3.5), Nil, n(2), Nil]
+        );
+
+        let mut t = table();
+        t.set_header(false);
+        t.zip_cols("sum", 0, 0, |a, b| a * b);
+        assert_eq!(
+            t.col(3).unwrap().cloned().collect::<Vec<_>>(),
+            vec![Nil, n(1), n(4), n(9), Nil]
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let mut t = table();
+        let err = Err(TableError::ColOutOfBounds { index: 3, cols: 3 });
+        assert_eq!(t.try_col_apply(3, |x| x).map(|_| ()), err.clone());
+        assert_eq!(t.try_scale_col(3, 2.into()).map(|_| ()), err.clone());
+        assert_eq!(
+            t.try_normalise_col(3, Norm::MinMax).map(|_| ()),
+            err.clone()
+        );
+        assert_eq!(t.try_zip_cols("x", 0, 3, |a, _| a.clone()).map(|_| ()), err);
+        assert_eq!(t, table());
+    }
+
+    #[test]
+    #[should_panic]
+    fn sum_panic() {
+        table().col_sum(3);
+    }
+}
@@ -0,0 +1,457 @@
+use super::*;
+use std::ops::{Bound, Range, RangeBounds};
+
+/// A borrowed view of a [`Table`], selecting a subset of its rows and columns.
+///
+/// Views are cheap to construct and do not copy any entries. Selections compose, each one
+/// selecting from the rows or columns of the _view_ rather than the underlying table. The header
+/// row, if any, is always part of the view and row selections index the _data_ rows.
+///
+/// ```rust
+/// # use table::*;
+/// use Entry::*;
+/// let r: Table<&str> = Table::from(vec![
+///     vec![Obj("a"), Obj("b"), Obj("c")],
+///     vec![Num(1.into()), Num(2.into()), Num(3.into())],
+///     vec![Num(4.into()), Num(5.into()), Num(6.into())],
+///     vec![Num(7.into()), Num(8.into()), Num(9.into())],
+/// ]);
+///
+/// let v = r.view().select_cols(&[2, 0]).slice_rows(1..);
+/// assert_eq!((v.rows_len(), v.cols_len()), (3, 2));
+/// assert_eq!(v.row(0).unwrap().collect::<Vec<_>>(), vec![&Obj("c"), &Obj("a")]);
+/// assert_eq!(v.row(1).unwrap().collect::<Vec<_>>(), vec![&Num(6.into()), &Num(4.into())]);
+///
+/// let v = v.filter_rows(|_, mut row| row.next() == Some(&Num(9.into())));
+/// assert_eq!(v.to_table().into_raw(), vec![
+///     vec![Obj("c"), Obj("a")],
+///     vec![Num(9.into()), Num(7.into())],
+/// ]);
+/// ```
+pub struct TableView<'a, T> {
+    table: &'a Table<T>,
+    rows: Sel,
+    cols: Sel,
+}
+
+/// An iterator over the entries of a row in a [`TableView`].
+pub struct ViewRow<'v, T> {
+    row: &'v [Entry<T>],
+    cols: SelIter<'v>,
+}
+
+/// A selection of indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Sel {
+    Range(Range<usize>),
+    Index(Vec<usize>),
+}
+
+#[derive(Debug, Clone)]
+enum SelIter<'a> {
+    Range(Range<usize>),
+    Index(std::slice::Iter<'a, usize>),
+}
+
+impl<T> Table<T> {
+    /// A borrowed view of the whole table. See [`TableView`].
+    pub fn view(&self) -> TableView<'_, T> {
+        TableView::from(self)
+    }
+}
+
+impl<'a, T> TableView<'a, T> {
+    /// The view has a header row.
+    pub fn header(&self) -> bool {
+        self.table.header && !self.table.data.is_empty()
+    }
+
+    /// The number of rows in the view, including the header.
+    pub fn rows_len(&self) -> usize {
+        self.rows.len() + self.s()
+    }
+
+    /// The number of columns in the view.
+    pub fn cols_len(&self) -> usize {
+        match self.rows_len() {
+            0 => 0,
+            _ => self.cols.len(),
+        }
+    }
+
+    /// The view has no entries in it.
+    pub fn is_empty(&self) -> bool {
+        self.rows_len() == 0 || self.cols_len() == 0
+    }
+
+    /// Retrieve a row of entries.
+    pub fn row(&self, index: usize) -> Option<ViewRow<'_, T>> {
+        let s = self.s();
+        let row = match index.checked_sub(s) {
+            None => 0,
+            Some(i) => self.rows.get(i)? + s,
+        };
+        Some(ViewRow {
+            row: &self.table.data[row],
+            cols: self.cols.iter(),
+        })
+    }
+
+    /// Retrieve a column of entries.
+    pub fn col(&self, index: usize) -> Option<impl Iterator<Item = &Entry<T>>> {
+        let col = self.cols.get(index)?;
+        let s = self.s();
+        let data = &self.table.data;
+        Some(
+            data.get(..s)
+                .unwrap_or_default()
+                .iter()
+                .chain(self.rows.iter().map(move |i| &data[i + s]))
+                .map(move |row| &row[col]),
+        )
+    }
+
+    /// Iterate over the rows.
+    pub fn rows(&self) -> impl Iterator<Item = ViewRow<'_, T>> {
+        (0..self.rows_len()).filter_map(move |i| self.row(i))
+    }
+
+    /// Iterate over the columns.
+    pub fn cols(&self) -> impl Iterator<Item = impl Iterator<Item = &Entry<T>>> {
+        (0..self.cols_len()).filter_map(move |i| self.col(i))
+    }
+
+    /// A view of a contiguous range of _data_ rows.
+    ///
+    /// # Panics
+    /// Panics if `range` is outside the data rows bounds.
+    pub fn slice_rows<R: RangeBounds<usize>>(&self, range: R) -> Self {
+        let range = bounded(range, self.rows.len(), panic_rows);
+        Self {
+            table: self.table,
+            rows: self.rows.slice(range),
+            cols: self.cols.clone(),
+        }
+    }
+
+    /// A view of the _data_ rows at `indices`, in the given order.
+    ///
+    /// # Panics
+    /// Panics if any index is outside the data rows bounds.
+    pub fn select_rows(&self, indices: &[usize]) -> Self {
+        Self {
+            table: self.table,
+            rows: self.rows.select(indices, panic_rows),
+            cols: self.cols.clone(),
+        }
+    }
+
+    /// A view of the _data_ rows which match the predicate `p`.
+    ///
+    /// The predicate supplies the data row index and the row entries.
+    pub fn filter_rows<P>(&self, p: P) -> Self
+    where
+        P: Fn(usize, ViewRow<T>) -> bool,
+    {
+        let s = self.s();
+        let rows = (0..self.rows.len())
+            .filter(|&i| p(i, self.row(i + s).expect("inside bounds")))
+            .filter_map(|i| self.rows.get(i))
+            .collect();
+        Self {
+            table: self.table,
+            rows: Sel::Index(rows),
+            cols: self.cols.clone(),
+        }
+    }
+
+    /// A view of a contiguous range of columns.
+    ///
+    /// # Panics
+    /// Panics if `range` is outside the columns bounds.
+    pub fn slice_cols<R: RangeBounds<usize>>(&self, range: R) -> Self {
+        let range = bounded(range, self.cols.len(), panic_cols);
+        Self {
+            table: self.table,
+            rows: self.rows.clone(),
+            cols: self.cols.slice(range),
+        }
+    }
+
+    /// A view of the columns at `indices`, in the given order.
+    ///
+    /// # Panics
+    /// Panics if any index is outside the columns bounds.
+    pub fn select_cols(&self, indices: &[usize]) -> Self {
+        Self {
+            table: self.table,
+            rows: self.rows.clone(),
+            cols: self.cols.select(indices, panic_cols),
+        }
+    }
+
+    /// Materialise the view into a new table, cloning the entries.
+    ///
+    /// # Parallelisation
+    /// `to_table` preallocates the output table and clones the rows in parallel.
+    pub fn to_table(&self) -> Table<T>
+    where
+        T: Clone + Send + Sync,
+    {
+        let cols = self.cols_len();
+        let mut data = alloc(self.rows_len(), cols);
+        data.par_iter_mut().enumerate().for_each(|(i, v)| {
+            v.extend(self.row(i).expect("inside bounds").cloned());
+        });
+
+        Table {
+            data,
+            header: self.table.header,
+            cols,
+        }
+    }
+
+    /// The header offset.
+    fn s(&self) -> usize {
+        if self.header() {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+impl<'a, T> From<&'a Table<T>> for TableView<'a, T> {
+    fn from(table: &'a Table<T>) -> Self {
+        let s = if table.header && !table.data.is_empty() {
+            1
+        } else {
+            0
+        };
+        Self {
+            table,
+            rows: Sel::Range(0..table.rows_len() - s),
+            cols: Sel::Range(0..table.cols_len()),
+        }
+    }
+}
+
+impl<'a, T> Clone for TableView<'a, T> {
+    fn clone(&self) -> Self {
+        Self {
+            table: self.table,
+            rows: self.rows.clone(),
+            cols: self.cols.clone(),
+        }
+    }
+}
+
+impl<'v, T> Iterator for ViewRow<'v, T> {
+    type Item = &'v Entry<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cols.next().map(|c| &self.row[c])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.cols.size_hint()
+    }
+}
+
+impl<'v, T> DoubleEndedIterator for ViewRow<'v, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.cols.next_back().map(|c| &self.row[c])
+    }
+}
+
+impl<'v, T> ExactSizeIterator for ViewRow<'v, T> {}
+
+impl<'v, T> Clone for ViewRow<'v, T> {
+    fn clone(&self) -> Self {
+        Self {
+            row: self.row,
+            cols: self.cols.clone(),
+        }
+    }
+}
+
+impl Sel {
+    fn len(&self) -> usize {
+        match self {
+            Sel::Range(x) => x.len(),
+            Sel::Index(x) => x.len(),
+        }
+    }
+
+    fn get(&self, i: usize) -> Option<usize> {
+        match self {
+            Sel::Range(x) => Some(x.start + i).filter(|_| i < x.len()),
+            Sel::Index(x) => x.get(i).copied(),
+        }
+    }
+
+    fn iter(&self) -> SelIter<'_> {
+        match self {
+            Sel::Range(x) => SelIter::Range(x.clone()),
+            Sel::Index(x) => SelIter::Index(x.iter()),
+        }
+    }
+
+    fn slice(&self, range: Range<usize>) -> Sel {
+        match self {
+            Sel::Range(x) => Sel::Range(x.start + range.start..x.start + range.end),
+            Sel::Index(x) => Sel::Index(x[range].to_vec()),
+        }
+    }
+
+    fn select(&self, indices: &[usize], panic: fn(usize, usize)) -> Sel {
+        let len = self.len();
+        Sel::Index(
+            indices
+                .iter()
+                .map(|&i| {
+                    self.get(i).unwrap_or_else(|| {
+                        panic(i, len);
+                        unreachable!()
+                    })
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<'a> Iterator for SelIter<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        match self {
+            SelIter::Range(x) => x.next(),
+            SelIter::Index(x) => x.next().copied(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            SelIter::Range(x) => x.size_hint(),
+            SelIter::Index(x) => x.size_hint(),
+        }
+    }
+}
+
+impl<'a> DoubleEndedIterator for SelIter<'a> {
+    fn next_back(&mut self) -> Option<usize> {
+        match self {
+            SelIter::Range(x) => x.next_back(),
+            SelIter::Index(x) => x.next_back().copied(),
+        }
+    }
+}
+
+/// Resolve `range` against a length of `len`, panicking if out of bounds.
+fn bounded<R: RangeBounds<usize>>(range: R, len: usize, panic: fn(usize, usize)) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&x) => x,
+        Bound::Excluded(&x) => x + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&x) => x + 1,
+        Bound::Excluded(&x) => x,
+        Bound::Unbounded => len,
+    };
+    if end > len {
+        panic(end, len);
+    }
+    if start > end {
+        panic(start, end);
+    }
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn n(x: i32) -> Entry<&'static str> {
+        Num(x.into())
+    }
+
+    fn table() -> Table<&'static str> {
+        Table::from(vec![
+            vec![Obj("a"), Obj("b"), Obj("c")],
+            vec![n(1), n(2), n(3)],
+            vec![n(4), n(5), n(6)],
+            vec![n(7), n(8), n(9)],
+            vec![n(10), n(11), n(12)],
+        ])
+    }
+
+    fn rows(v: &TableView<&'static str>) -> Vec<Vec<Entry<&'static str>>> {
+        v.rows().map(|r| r.cloned().collect()).collect()
+    }
+
+    #[test]
+    fn whole_view() {
+        let t = table();
+        let v = t.view();
+        assert!(v.header());
+        assert_eq!((v.rows_len(), v.cols_len()), (5, 3));
+        assert_eq!(rows(&v), t.clone().into_raw());
+        assert_eq!(
+            v.cols().map(|c| c.count()).collect::<Vec<_>>(),
+            vec![5, 5, 5]
+        );
+        assert_eq!(v.to_table(), t);
+        assert!(v.row(5).is_none());
+        assert!(v.col(3).is_none());
+
+        let e = Table::<()>::new();
+        assert!(e.view().is_empty());
+        assert_eq!(e.view().to_table(), e);
+    }
+
+    #[test]
+    fn composing_views() {
+        let t = table();
+        let v = t.view().slice_rows(1..3).select_cols(&[2, 0]);
+        assert_eq!(
+            rows(&v),
+            vec![vec![Obj("c"), Obj("a")], vec![n(6), n(4)], vec![n(9), n(7)]]
+        );
+        assert_eq!(
+            v.col(1).unwrap().collect::<Vec<_>>(),
+            vec![&Obj("a"), &n(4), &n(7)]
+        );
+
+        let v = v.slice_cols(1..).select_rows(&[1, 1, 0]);
+        assert_eq!(
+            rows(&v),
+            vec![vec![Obj("a")], vec![n(7)], vec![n(7)], vec![n(4)]]
+        );
+
+        let v = t
+            .view()
+            .slice_rows(..=2)
+            .filter_rows(|i, mut r| i == 0 || r.nth(1) == Some(&n(8)));
+        assert_eq!(v.rows_len(), 3);
+        assert_eq!(v.row(2).unwrap().next_back(), Some(&n(9)));
+
+        let mut t = table();
+        t.set_header(false);
+        let v = t.view().slice_rows(..1).slice_cols(2..);
+        assert!(!v.header());
+        assert_eq!(v.to_table().into_raw(), vec![vec![Obj("c")]]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_panic() {
+        table().view().slice_rows(1..5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn select_panic() {
+        table().view().slice_cols(1..).select_cols(&[2]);
+    }
+}
@@ -0,0 +1,413 @@
+use super::*;
+use std::{collections::HashMap, hash::Hash};
+
+/// A function evaluated over a window of rows, see [`Table::window_col`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum WindowFn {
+    /// Mean of the last `n` entries, including the current row.
+    RollingMean(usize),
+    /// Sum of the last `n` entries, including the current row.
+    RollingSum(usize),
+    /// Minimum of the last `n` entries, including the current row.
+    RollingMin(usize),
+    /// Maximum of the last `n` entries, including the current row.
+    RollingMax(usize),
+    /// Cumulative sum of the entries up to and including the current row, skipping nils. Once the
+    /// sum overflows, the remaining rows of the partition are nil.
+    CumSum,
+    /// Cumulative product of the entries up to and including the current row, skipping nils. Once
+    /// the product overflows, the remaining rows of the partition are nil.
+    CumProd,
+    /// The entry `n` rows before the current row.
+    Lag(usize),
+    /// The entry `n` rows after the current row.
+    Lead(usize),
+    /// The 1-based position of the row.
+    RowNumber,
+    /// The 1-based rank of the row, with gaps after ties.
+    Rank,
+    /// The 1-based rank of the row, without gaps after ties.
+    DenseRank,
+}
+
+/// Defines the partitioning and ordering of rows for a [`WindowFn`].
+///
+/// By default all _data_ rows are a single partition in table order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Window {
+    partition: Vec<usize>,
+    order: Option<(usize, Direction)>,
+}
+
+impl Window {
+    /// A window over all _data_ rows, in table order.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Partition rows by the entries in the key columns. Each partition is evaluated
+    /// independently.
+    pub fn partition_by(mut self, cols: &[usize]) -> Self {
+        self.partition = cols.to_vec();
+        self
+    }
+
+    /// Order rows within each partition by column `col`, using [`Entry::total_cmp`].
+    ///
+    /// The ordering is stable, rows with equal entries keep their table order.
+    pub fn order_by(mut self, col: usize, direction: Direction) -> Self {
+        self.order = Some((col, direction));
+        self
+    }
+}
+
+impl<T> Table<T> {
+    /// Evaluate the window function `func` over column `col` and add the results as a new column.
+    ///
+    /// Rows are grouped into partitions and ordered as defined by `window`. Results are placed in
+    /// the row they were calculated for, so the table order is unchanged. If the table has a
+    /// header row, `name` is used as the header of the new column.
+    ///
+    /// Numeric functions only consider `Num` entries. Rolling functions are [`Nil`] until the
+    /// window is full, or if the window contains a non-numeric entry. Ranking functions rank by the
+    /// window's order column, or by `col` in ascending order if no order is defined.
+    ///
+    /// ```rust
+    /// # use table::*;
+    /// use Entry::*;
+    /// let mut r: Table<&str> = Table::new();
+    /// r.add_rows(
+    ///     vec![
+    ///         vec![Obj("region"), Obj("day"), Obj("demand")].into_iter(),
+    ///         vec![Obj("NSW"), Num(2.into()), Num(20.into())].into_iter(),
+    ///         vec![Obj("VIC"), Num(1.into()), Num(5.into())].into_iter(),
+    ///         vec![Obj("NSW"), Num(1.into()), Num(10.into())].into_iter(),
+    ///         vec![Obj("NSW"), Num(3.into()), Num(30.into())].into_iter(),
+    ///     ].into_iter()
+    /// );
+    ///
+    /// let by_day = Window::new().partition_by(&[0]).order_by(1, Direction::Asc);
+    /// r.window_col("cumsum", 2, WindowFn::CumSum, &by_day);
+    /// r.window_col("avg", 2, WindowFn::RollingMean(2), &by_day);
+    ///
+    /// let cumsum: Vec<_> = r.col(3).unwrap().skip(1).cloned().collect();
+    /// assert_eq!(cumsum, vec![Num(30.into()), Num(5.into()), Num(10.into()), Num(60.into())]);
+    /// let avg: Vec<_> = r.col(4).unwrap().skip(1).cloned().collect();
+    /// assert_eq!(avg, vec![Num(15.into()), Nil, Nil, Num(25.into())]);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if `col` or a column of `window` is outside the columns bounds. See
+    /// [`Table::try_window_col`].
+    ///
+    /// # Parallelisation
+    /// `window_col` evaluates partitions in parallel.
+    pub fn window_col<'n>(
+        &mut self,
+        name: &'n str,
+        col: usize,
+        func: WindowFn,
+        window: &Window,
+    ) -> &mut Self
+    where
+        T: Clone + Hash + Ord + Send + Sync + From<&'n str>,
+    {
+        or_panic(self.try_window_col(name, col, func, window))
+    }
+
+    /// Evaluate the window function `func` over column `col` and add the results as a new column.
+    /// See [`Table::window_col`].
+    ///
+    /// Returns an error if `col` or a column of `window` is outside the columns bounds.
+    ///
+    /// # Parallelisation
+    /// `try_window_col` evaluates partitions in parallel.
+    pub fn try_window_col<'n>(
+        &mut self,
+        name: &'n str,
+        col: usize,
+        func: WindowFn,
+        window: &Window,
+    ) -> Result<&mut Self, TableError>
+    where
+        T: Clone + Hash + Ord + Send + Sync + From<&'n str>,
+    {
+        let order = match (window.order, func) {
+            (None, WindowFn::Rank | WindowFn::DenseRank) => Some((col, Direction::Asc)),
+            (order, _) => order,
+        };
+        for &c in once(&col)
+            .chain(&window.partition)
+            .chain(order.as_ref().map(|x| &x.0))
+        {
+            TableError::check_col(c, self.cols_len())?;
+        }
+
+        let s = if self.header { 1 } else { 0 };
+        let data = self.data.get(s..).unwrap_or_default();
+
+        // group row indices by partition keys, in table order
+        let mut keys: HashMap<Vec<&Entry<T>>, usize> = HashMap::new();
+        let mut partitions: Vec<Vec<usize>> = Vec::new();
+        for (i, row) in data.iter().enumerate() {
+            let key = window.partition.iter().map(|&c| &row[c]).collect();
+            let n = partitions.len();
+            let p = *keys.entry(key).or_insert(n);
+            if p == n {
+                partitions.push(Vec::new());
+            }
+            partitions[p].push(i);
+        }
+
+        let results = partitions
+            .into_par_iter()
+            .flat_map_iter(|mut rows| {
+                if let Some((o, dir)) = order {
+                    rows.sort_by(|&a, &b| match dir {
+                        Direction::Asc => data[a][o].total_cmp(&data[b][o]),
+                        Direction::Desc => data[b][o].total_cmp(&data[a][o]),
+                    });
+                }
+                let key = order.map_or(col, |x| x.0);
+                let entries = rows.iter().map(|&r| &data[r][col]).collect::<Vec<_>>();
+                let keys = rows.iter().map(|&r| &data[r][key]).collect::<Vec<_>>();
+                rows.into_iter().zip(eval(func, &entries, &keys))
+            })
+            .collect::<Vec<_>>();
+
+        let mut new = Vec::with_capacity(self.rows_len());
+        if self.header {
+            new.push(Obj(T::from(name)));
+        }
+        new.resize_with(s + data.len(), Default::default);
+        for (i, e) in results {
+            new[s + i] = e;
+        }
+
+        Ok(self.add_col(new.into_iter()))
+    }
+}
+
+/// Evaluate `func` over the ordered `entries` of a partition, ranking by `keys`.
+fn eval<T: Clone + Eq>(func: WindowFn, entries: &[&Entry<T>], keys: &[&Entry<T>]) -> Vec<Entry<T>> {
+    use WindowFn::*;
+    let n = |x: usize| Num(x.into());
+    let len = entries.len();
+
+    match func {
+        RollingMean(w) | RollingSum(w) | RollingMin(w) | RollingMax(w) => (0..len)
+            .map(|i| {
+                let nums = match (i + 1).checked_sub(w) {
+                    Some(s) if w > 0 => entries[s..=i].iter().map(|e| e.num()).collect(),
+                    _ => None,
+                };
+                nums.and_then(|nums: Vec<Number>| match func {
+                    RollingMean(_) => {
+                        num::float(nums.iter().map(Number::as_f64).sum::<f64>() / w as f64)
+                    }
+                    RollingSum(_) => nums.into_iter().try_fold(Number::from(0), num::add),
+                    RollingMin(_) => nums.into_iter().min(),
+                    _ => nums.into_iter().max(),
+                })
+                .map_or(Nil, Num)
+            })
+            .collect(),
+        CumSum | CumProd => {
+            let mut acc: Option<Number> = None;
+            // once the running total overflows, it stays Nil for the rest of the partition
+            let mut overflowed = false;
+            entries
+                .iter()
+                .map(|e| match e.num() {
+                    Some(x) if !overflowed => {
+                        acc = match (acc, func) {
+                            (None, _) => Some(x),
+                            (Some(a), CumSum) => num::add(a, x),
+                            (Some(a), _) => num::mul(a, x),
+                        };
+                        overflowed = acc.is_none();
+                        acc.map_or(Nil, Num)
+                    }
+                    _ => Nil,
+                })
+                .collect()
+        }
+        Lag(k) => (0..len)
+            .map(|i| i.checked_sub(k).map_or(Nil, |j| entries[j].clone()))
+            .collect(),
+        Lead(k) => (0..len)
+            .map(|i| match i.checked_add(k).filter(|&j| j < len) {
+                Some(j) => entries[j].clone(),
+                None => Nil,
+            })
+            .collect(),
+        RowNumber => (1..=len).map(n).collect(),
+        Rank | DenseRank => {
+            let mut rank = 0;
+            let mut dense = 0;
+            (0..len)
+                .map(|i| {
+                    if i == 0 || keys[i] != keys[i - 1] {
+                        rank = i + 1;
+                        dense += 1;
+                    }
+                    match func {
+                        Rank => n(rank),
+                        _ => n(dense),
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use WindowFn::*;
+
+    fn n<T>(x: i32) -> Entry<T> {
+        Num(x.into())
+    }
+
+    fn o(s: &'static str) -> Entry<&'static str> {
+        Obj(s)
+    }
+
+    fn table() -> Table<&'static str> {
+        Table::from(vec![
+            vec![o("region"), o("t"), o("v")],
+            vec![o("a"), n(3), n(3)],
+            vec![o("b"), n(1), n(10)],
+            vec![o("a"), n(1), n(1)],
+            vec![o("a"), n(2), Nil],
+            vec![o("b"), n(2), n(20)],
+            vec![o("a"), n(4), n(4)],
+        ])
+    }
+
+    fn eval_col(func: WindowFn, window: &Window) -> Vec<Entry<&'static str>> {
+        let mut t = table();
+        t.window_col("x", 2, func, window);
+        t.col(3).unwrap().cloned().collect()
+    }
+
+    #[test]
+    fn no_partition() {
+        let w = Window::new();
+        assert_eq!(
+            eval_col(RowNumber, &w),
+            vec![o("x"), n(1), n(2), n(3), n(4), n(5), n(6)]
+        );
+        assert_eq!(
+            eval_col(CumSum, &w),
+            vec![o("x"), n(3), n(13), n(14), Nil, n(34), n(38)]
+        );
+        assert_eq!(
+            eval_col(Lag(1), &w),
+            vec![o("x"), Nil, n(3), n(10), n(1), Nil, n(20)]
+        );
+        assert_eq!(
+            eval_col(Lead(2), &w),
+            vec![o("x"), n(1), Nil, n(20), n(4), Nil, Nil]
+        );
+        assert_eq!(
+            eval_col(RollingSum(2), &w),
+            vec![o("x"), Nil, n(13), n(11), Nil, Nil, n(24)]
+        );
+    }
+
+    #[test]
+    fn cumulative_overflow() {
+        let f = |x: f64| -> Entry<&'static str> { Num(x.into()) };
+        let entries = [f(f64::MAX), f(f64::MAX), n(1), Nil, n(2)];
+        let entries = entries.iter().collect::<Vec<_>>();
+        assert_eq!(
+            eval(CumSum, &entries, &entries),
+            vec![f(f64::MAX), Nil, Nil, Nil, Nil]
+        );
+
+        let entries = [n(2), f(1e200), f(1e200), n(1)];
+        let entries = entries.iter().collect::<Vec<_>>();
+        assert_eq!(
+            eval(CumProd, &entries, &entries),
+            vec![n(2), f(2e200), Nil, Nil]
+        );
+    }
+
+    #[test]
+    fn partitioned_and_ordered() {
+        let w = Window::new().partition_by(&[0]).order_by(1, Direction::Asc);
+        // a ordered by t: 1 (v 1), 2 (v nil), 3 (v 3), 4 (v 4)
+        // b ordered by t: 1 (v 10), 2 (v 20)
+        assert_eq!(
+            eval_col(RowNumber, &w),
+            vec![o("x"), n(3), n(1), n(1), n(2), n(2), n(4)]
+        );
+        assert_eq!(
+            eval_col(CumProd, &w),
+            vec![o("x"), n(3), n(10), n(1), Nil, n(200), n(12)]
+        );
+        assert_eq!(
+            eval_col(RollingMean(2), &w),
+            vec![o("x"), Nil, Nil, Nil, Nil, n(15), Num(3.5.into())]
+        );
+        assert_eq!(
+            eval_col(RollingMin(1), &w),
+            vec![o("x"), n(3), n(10), n(1), Nil, n(20), n(4)]
+        );
+        assert_eq!(
+            eval_col(RollingMax(2), &w),
+            vec![o("x"), Nil, Nil, Nil, Nil, n(20), n(4)]
+        );
+        assert_eq!(
+            eval_col(Lag(1), &w),
+            vec![o("x"), Nil, Nil, Nil, n(1), n(10), n(3)]
+        );
+
+        let w = Window::new()
+            .partition_by(&[0])
+            .order_by(1, Direction::Desc);
+        assert_eq!(
+            eval_col(RowNumber, &w),
+            vec![o("x"), n(2), n(2), n(4), n(3), n(1), n(1)]
+        );
+    }
+
+    #[test]
+    fn ranking() {
+        let mut t = Table::from(vec![
+            vec![o("k"), o("score")],
+            vec![o("a"), n(5)],
+            vec![o("a"), n(7)],
+            vec![o("a"), n(5)],
+            vec![o("a"), n(9)],
+            vec![o("b"), n(1)],
+        ]);
+        let w = Window::new().partition_by(&[0]).order_by(1, Direction::Asc);
+        t.window_col("rank", 1, Rank, &w)
+            .window_col("dense", 1, DenseRank, &w)
+            .window_col("rank_no_order", 1, Rank, &Window::new());
+
+        let col = |i| t.col(i).unwrap().skip(1).cloned().collect::<Vec<_>>();
+        assert_eq!(col(2), vec![n(1), n(3), n(1), n(4), n(1)]);
+        assert_eq!(col(3), vec![n(1), n(2), n(1), n(3), n(1)]);
+        // without an order, rank by the column itself
+        assert_eq!(col(4), vec![n(2), n(4), n(2), n(5), n(1)]);
+    }
+
+    #[test]
+    fn no_header() {
+        let mut t = table();
+        t.set_header(false);
+        t.window_col("x", 1, RowNumber, &Window::new());
+        assert_eq!(t.cols_len(), 4);
+        assert_eq!(t.row(0).unwrap().nth(3), Some(&n(1)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn window_panic() {
+        table().window_col("x", 2, CumSum, &Window::new().order_by(3, Direction::Asc));
+    }
+}